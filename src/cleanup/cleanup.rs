@@ -5,13 +5,18 @@ use crate::Persistent;
 /// A trait implemented by [`App`] to provide state cleanup.
 pub trait AddStateCleanup {
     /// Adds [`cleanup_nonpersistent_entities`] to each state's [`OnExit`] schedule.
+    ///
+    /// Since [`App::add_state`] replaces the [`OnExit`] schedules, call this after it. States
+    /// added with `AddFixedState` can be added before or after this.
     fn add_state_cleanup<T: States>(&mut self) -> &mut Self;
 }
 
 impl AddStateCleanup for App {
     fn add_state_cleanup<T: States>(&mut self) -> &mut Self {
         for state in T::variants() {
-            self.add_system(cleanup_nonpersistent_entities.in_schedule(OnExit(state)));
+            self.edit_schedule(OnExit(state), |schedule| {
+                schedule.add_system(cleanup_nonpersistent_entities);
+            });
         }
         self
     }
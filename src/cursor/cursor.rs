@@ -23,7 +23,8 @@ impl Plugin for CursorPlugin {
 }
 
 /// Helper resource for getting cursor information.
-#[derive(Default, Resource, Debug)]
+#[derive(Clone, Default, Resource, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Cursor {
     /// The position of the cursor in the window, with 0,0 being the bottom left.
     ///
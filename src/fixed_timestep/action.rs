@@ -0,0 +1,234 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use bevy::{input::InputSystem, prelude::*};
+
+use super::{sync_input, AddFixedInput, FixedInputSystem};
+
+/// System set for updating action state from an [`ActionMap`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub struct ActionMapSystem;
+
+/// A trait implemented by [`App`] allowing mapping inputs to user defined actions.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::prelude::*;
+/// #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+/// pub enum Action {
+///     Jump,
+/// }
+///
+/// App::new()
+///     .add_action_map::<Action>()
+///     .insert_resource(
+///         ActionMap::new()
+///             .with_binding(Action::Jump, KeyCode::Space)
+///             .with_binding(Action::Jump, MouseButton::Left),
+///     );
+/// ```
+pub trait AddActionMap {
+    /// Add an [`ActionMap`] for the action type `A`, along with a frame rate [`Input`] and a
+    /// [`FixedInput`](`super::FixedInput`) for `A`.
+    fn add_action_map<A: Copy + Eq + Hash + Send + Sync + 'static>(&mut self) -> &mut Self;
+}
+
+impl AddActionMap for App {
+    fn add_action_map<A: Copy + Eq + Hash + Send + Sync + 'static>(&mut self) -> &mut Self {
+        self.init_resource::<ActionMap<A>>()
+            .init_resource::<Input<A>>()
+            .add_system(
+                action_map_update::<A>
+                    .in_set(ActionMapSystem)
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(InputSystem)
+                    .before(FixedInputSystem),
+            )
+            .add_fixed_input::<A>();
+        self
+    }
+}
+
+/// A physical input which can be bound to an action in an [`ActionMap`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum InputBinding {
+    /// A keyboard key, by key code.
+    Key(KeyCode),
+    /// A keyboard key, by scan code.
+    ScanCode(ScanCode),
+    /// A mouse button.
+    Mouse(MouseButton),
+    /// A button on a specific gamepad.
+    Gamepad(GamepadButton),
+}
+
+impl InputBinding {
+    fn pressed(self, inputs: &BindingInputs) -> bool {
+        match self {
+            Self::Key(key_code) => inputs.key_codes.pressed(key_code),
+            Self::ScanCode(scan_code) => inputs.scan_codes.pressed(scan_code),
+            Self::Mouse(mouse_button) => inputs.mouse_buttons.pressed(mouse_button),
+            Self::Gamepad(gamepad_button) => inputs.gamepad_buttons.pressed(gamepad_button),
+        }
+    }
+
+    fn just_pressed(self, inputs: &BindingInputs) -> bool {
+        match self {
+            Self::Key(key_code) => inputs.key_codes.just_pressed(key_code),
+            Self::ScanCode(scan_code) => inputs.scan_codes.just_pressed(scan_code),
+            Self::Mouse(mouse_button) => inputs.mouse_buttons.just_pressed(mouse_button),
+            Self::Gamepad(gamepad_button) => inputs.gamepad_buttons.just_pressed(gamepad_button),
+        }
+    }
+}
+
+impl From<KeyCode> for InputBinding {
+    fn from(key_code: KeyCode) -> Self {
+        Self::Key(key_code)
+    }
+}
+
+impl From<ScanCode> for InputBinding {
+    fn from(scan_code: ScanCode) -> Self {
+        Self::ScanCode(scan_code)
+    }
+}
+
+impl From<MouseButton> for InputBinding {
+    fn from(mouse_button: MouseButton) -> Self {
+        Self::Mouse(mouse_button)
+    }
+}
+
+impl From<GamepadButton> for InputBinding {
+    fn from(gamepad_button: GamepadButton) -> Self {
+        Self::Gamepad(gamepad_button)
+    }
+}
+
+/// Maps physical inputs ([`InputBinding`]) to a user defined action type `A`.
+///
+/// Each frame, an action is pressed if any of its bindings are pressed. The resulting state is
+/// available in [`Input<A>`] for regular systems and [`FixedInput<A>`](`super::FixedInput`) for
+/// fixed timestep systems.
+///
+/// Bindings can be changed at runtime, and the map can be saved to or loaded from a config file
+/// with the `serde` feature.
+#[derive(Clone, Debug, Resource)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ActionMap<A: Copy + Eq + Hash + Send + Sync + 'static> {
+    bindings: HashMap<A, Vec<InputBinding>>,
+}
+
+impl<A: Copy + Eq + Hash + Send + Sync + 'static> Default for ActionMap<A> {
+    fn default() -> Self {
+        Self {
+            bindings: HashMap::new(),
+        }
+    }
+}
+
+impl<A: Copy + Eq + Hash + Send + Sync + 'static> ActionMap<A> {
+    /// Create an empty [`ActionMap`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns this [`ActionMap`] with an additional binding for `action`.
+    #[must_use]
+    pub fn with_binding(mut self, action: A, binding: impl Into<InputBinding>) -> Self {
+        self.bind(action, binding);
+        self
+    }
+
+    /// Bind an input to `action`. Returns `false` if the binding already existed.
+    pub fn bind(&mut self, action: A, binding: impl Into<InputBinding>) -> bool {
+        let binding = binding.into();
+        let bindings = self.bindings.entry(action).or_default();
+        if bindings.contains(&binding) {
+            false
+        } else {
+            bindings.push(binding);
+            true
+        }
+    }
+
+    /// Remove an input from `action`. Returns `false` if the binding did not exist.
+    pub fn unbind(&mut self, action: A, binding: impl Into<InputBinding>) -> bool {
+        let binding = binding.into();
+        if let Some(bindings) = self.bindings.get_mut(&action) {
+            if let Some(index) = bindings.iter().position(|other| *other == binding) {
+                bindings.remove(index);
+                if bindings.is_empty() {
+                    self.bindings.remove(&action);
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Replace all bindings for `action` with a single binding.
+    pub fn rebind(&mut self, action: A, binding: impl Into<InputBinding>) {
+        self.bindings.insert(action, vec![binding.into()]);
+    }
+
+    /// Remove all bindings for `action`.
+    pub fn clear(&mut self, action: A) {
+        self.bindings.remove(&action);
+    }
+
+    /// Remove all bindings for all actions.
+    pub fn clear_all(&mut self) {
+        self.bindings.clear();
+    }
+
+    /// Get the bindings for `action`.
+    #[must_use]
+    pub fn bindings(&self, action: A) -> &[InputBinding] {
+        self.bindings.get(&action).map_or(&[], |bindings| bindings)
+    }
+
+    /// Iterate over all actions with at least one binding.
+    pub fn actions(&self) -> impl Iterator<Item = A> + '_ {
+        self.bindings.keys().copied()
+    }
+}
+
+struct BindingInputs<'a> {
+    key_codes: &'a Input<KeyCode>,
+    scan_codes: &'a Input<ScanCode>,
+    mouse_buttons: &'a Input<MouseButton>,
+    gamepad_buttons: &'a Input<GamepadButton>,
+}
+
+fn action_map_update<A: Copy + Eq + Hash + Send + Sync + 'static>(
+    mut action_input: ResMut<Input<A>>,
+    action_map: Res<ActionMap<A>>,
+    key_codes: Res<Input<KeyCode>>,
+    scan_codes: Res<Input<ScanCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+) {
+    let inputs = BindingInputs {
+        key_codes: key_codes.as_ref(),
+        scan_codes: scan_codes.as_ref(),
+        mouse_buttons: mouse_buttons.as_ref(),
+        gamepad_buttons: gamepad_buttons.as_ref(),
+    };
+    let mut pressed = HashSet::new();
+    let mut just_pressed = HashSet::new();
+    for (action, bindings) in &action_map.bindings {
+        if bindings.iter().any(|binding| binding.pressed(&inputs)) {
+            pressed.insert(*action);
+        }
+        if bindings.iter().any(|binding| binding.just_pressed(&inputs)) {
+            just_pressed.insert(*action);
+        }
+    }
+    sync_input(&mut action_input, &pressed, &just_pressed);
+}
@@ -0,0 +1,285 @@
+use std::{collections::HashMap, hash::Hash};
+
+use bevy::{
+    input::{
+        mouse::{MouseMotion, MouseWheel},
+        InputSystem,
+    },
+    prelude::*,
+};
+
+use super::{
+    add_fixed_schedule_local, for_each_fixed_schedule, FixedInputSystem, FixedScheduleLocal,
+    FixedSet,
+};
+
+/// A trait implemented by [`App`] allowing adding more fixed timestep axis types.
+pub trait AddFixedAxis {
+    /// Add a [`FixedAxis`] version of [`Axis`] for T, combining values between ticks using
+    /// `combine`. This is called automatically for [`GamepadAxis`] and [`GamepadButton`] (using
+    /// [`AxisCombine::Latest`]).
+    fn add_fixed_axis<T: Copy + Eq + Hash + Send + Sync + 'static>(
+        &mut self,
+        combine: AxisCombine,
+    ) -> &mut Self;
+}
+
+impl AddFixedAxis for App {
+    fn add_fixed_axis<T: Copy + Eq + Hash + Send + Sync + 'static>(
+        &mut self,
+        combine: AxisCombine,
+    ) -> &mut Self {
+        init_fixed_axis::<T>(self, combine).add_system(
+            fixed_axis_update::<T>
+                .in_set(FixedInputSystem)
+                .in_base_set(CoreSet::PreUpdate)
+                .after(InputSystem),
+        );
+        self
+    }
+}
+
+fn init_fixed_axis<T: Copy + Eq + Hash + Send + Sync + 'static>(
+    app: &mut App,
+    combine: AxisCombine,
+) -> &mut App {
+    app.insert_resource(FixedAxis::<T>::new(combine));
+    for_each_fixed_schedule(app, add_fixed_axis_clear::<T>);
+    add_fixed_schedule_local::<FixedAxis<T>>(app);
+    app
+}
+
+fn add_fixed_axis_clear<T: Copy + Eq + Hash + Send + Sync + 'static>(schedule: &mut Schedule) {
+    schedule.add_system(
+        fixed_axis_clear::<T>
+            .in_set(FixedInputSystem)
+            .in_base_set(FixedSet::Last),
+    );
+}
+
+pub(crate) struct FixedTimestepAxisPlugin;
+
+impl Plugin for FixedTimestepAxisPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_fixed_axis::<GamepadAxis>(AxisCombine::Latest)
+            .add_fixed_axis::<GamepadButton>(AxisCombine::Latest);
+        init_fixed_axis::<MouseAxis>(app, AxisCombine::Sum).add_system(
+            fixed_axis_mouse_update
+                .in_set(FixedInputSystem)
+                .in_base_set(CoreSet::PreUpdate)
+                .after(InputSystem),
+        );
+    }
+}
+
+/// Relative mouse movement axes, available through [`FixedAxis<MouseAxis>`].
+///
+/// Values are deltas, accumulated from [`MouseMotion`] and [`MouseWheel`] events until the next
+/// fixed tick.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum MouseAxis {
+    /// Horizontal mouse motion.
+    MotionX,
+    /// Vertical mouse motion.
+    MotionY,
+    /// Horizontal scroll wheel movement, in the unit reported by [`MouseWheel`].
+    WheelX,
+    /// Vertical scroll wheel movement, in the unit reported by [`MouseWheel`].
+    WheelY,
+}
+
+/// How values received between fixed ticks are combined in a [`FixedAxis`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum AxisCombine {
+    /// Add all values together, resetting to 0 after each tick. Useful for deltas, such as mouse
+    /// motion.
+    Sum,
+    /// Keep the most recent value, which remains after each tick. Useful for absolute positions,
+    /// such as gamepad sticks.
+    #[default]
+    Latest,
+    /// Keep the value with the largest magnitude, resetting to 0 after each tick.
+    Max,
+}
+
+/// A fixed timestep version of [`Axis`].
+///
+/// Values are collected each frame and combined according to [`AxisCombine`], so fixed timestep
+/// systems see consistent values regardless of how many frames passed between ticks.
+///
+/// Each axis can have its own dead zone (see [`FixedAxis::set_dead_zone`]), falling back to a
+/// default dead zone shared by all axes (see [`FixedAxis::set_default_dead_zone`]).
+#[derive(Clone, Debug, Reflect, Resource)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[reflect(Default)]
+pub struct FixedAxis<T: Copy + Eq + Hash + Send + Sync + 'static> {
+    #[reflect(ignore)]
+    values: HashMap<T, f32>,
+    combine: AxisCombine,
+    #[reflect(ignore)]
+    dead_zones: HashMap<T, f32>,
+    default_dead_zone: f32,
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static> Default for FixedAxis<T> {
+    fn default() -> Self {
+        Self::new(AxisCombine::default())
+    }
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static> FixedAxis<T> {
+    /// Create an empty [`FixedAxis`] with the given combine mode and no dead zone.
+    #[must_use]
+    pub fn new(combine: AxisCombine) -> Self {
+        Self {
+            values: HashMap::new(),
+            combine,
+            dead_zones: HashMap::new(),
+            default_dead_zone: 0.,
+        }
+    }
+
+    /// Get the value of an axis, or 0 if the value is within the dead zone.
+    #[must_use]
+    pub fn get(&self, axis: T) -> f32 {
+        let value = self.get_raw(axis);
+        if value.abs() <= self.dead_zone(axis) {
+            0.
+        } else {
+            value
+        }
+    }
+
+    /// Get the value of an axis, ignoring the dead zone.
+    #[must_use]
+    pub fn get_raw(&self, axis: T) -> f32 {
+        self.values.get(&axis).copied().unwrap_or(0.)
+    }
+
+    /// Combine a new value into an axis according to the [`AxisCombine`] mode.
+    pub fn record(&mut self, axis: T, value: f32) {
+        let current = self.values.entry(axis).or_insert(0.);
+        match self.combine {
+            AxisCombine::Sum => *current += value,
+            AxisCombine::Latest => *current = value,
+            AxisCombine::Max => {
+                if value.abs() > current.abs() {
+                    *current = value;
+                }
+            }
+        }
+    }
+
+    /// Overwrite the value of an axis, ignoring the [`AxisCombine`] mode.
+    pub fn set(&mut self, axis: T, value: f32) {
+        self.values.insert(axis, value);
+    }
+
+    /// Remove an axis, causing it to read as 0.
+    pub fn remove(&mut self, axis: T) {
+        self.values.remove(&axis);
+    }
+
+    /// Iterate over all axes which have a value.
+    pub fn axes(&self) -> impl Iterator<Item = T> + '_ {
+        self.values.keys().copied()
+    }
+
+    /// Get the [`AxisCombine`] mode.
+    #[must_use]
+    pub fn combine(&self) -> AxisCombine {
+        self.combine
+    }
+
+    /// Set the [`AxisCombine`] mode.
+    pub fn set_combine(&mut self, combine: AxisCombine) {
+        self.combine = combine;
+    }
+
+    /// Get the dead zone of an axis. Values with a magnitude less than or equal to the dead zone
+    /// read as 0.
+    #[must_use]
+    pub fn dead_zone(&self, axis: T) -> f32 {
+        self.dead_zones
+            .get(&axis)
+            .copied()
+            .unwrap_or(self.default_dead_zone)
+    }
+
+    /// Set the dead zone of an axis. Values with a magnitude less than or equal to the dead zone
+    /// read as 0.
+    pub fn set_dead_zone(&mut self, axis: T, dead_zone: f32) {
+        self.dead_zones.insert(axis, dead_zone.abs());
+    }
+
+    /// Remove the dead zone of an axis, causing it to use the default dead zone.
+    pub fn remove_dead_zone(&mut self, axis: T) {
+        self.dead_zones.remove(&axis);
+    }
+
+    /// Get the dead zone used by axes without their own dead zone.
+    #[must_use]
+    pub fn default_dead_zone(&self) -> f32 {
+        self.default_dead_zone
+    }
+
+    /// Set the dead zone used by axes without their own dead zone.
+    pub fn set_default_dead_zone(&mut self, dead_zone: f32) {
+        self.default_dead_zone = dead_zone.abs();
+    }
+
+    /// Clear values which should not persist past a tick. Only [`AxisCombine::Latest`] values
+    /// persist.
+    pub fn clear(&mut self) {
+        if self.combine != AxisCombine::Latest {
+            self.values.clear();
+        }
+    }
+}
+
+fn fixed_axis_update<T: Copy + Eq + Hash + Send + Sync + 'static>(
+    mut fixed_axis: ResMut<FixedAxis<T>>,
+    mut locals: ResMut<FixedScheduleLocal<FixedAxis<T>>>,
+    axis: Res<Axis<T>>,
+) {
+    for fixed_axis in std::iter::once(fixed_axis.as_mut()).chain(locals.iter_mut()) {
+        for device in axis.devices() {
+            if let Some(value) = axis.get(*device) {
+                fixed_axis.record(*device, value);
+            }
+        }
+        if fixed_axis.combine() == AxisCombine::Latest {
+            fixed_axis
+                .values
+                .retain(|device, _| axis.get(*device).is_some());
+        }
+    }
+}
+
+fn fixed_axis_mouse_update(
+    mut fixed_axis: ResMut<FixedAxis<MouseAxis>>,
+    mut locals: ResMut<FixedScheduleLocal<FixedAxis<MouseAxis>>>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+) {
+    let mouse_motions: Vec<&MouseMotion> = mouse_motion_events.iter().collect();
+    let mouse_wheels: Vec<&MouseWheel> = mouse_wheel_events.iter().collect();
+    for fixed_axis in std::iter::once(fixed_axis.as_mut()).chain(locals.iter_mut()) {
+        for mouse_motion in &mouse_motions {
+            fixed_axis.record(MouseAxis::MotionX, mouse_motion.delta.x);
+            fixed_axis.record(MouseAxis::MotionY, mouse_motion.delta.y);
+        }
+        for mouse_wheel in &mouse_wheels {
+            fixed_axis.record(MouseAxis::WheelX, mouse_wheel.x);
+            fixed_axis.record(MouseAxis::WheelY, mouse_wheel.y);
+        }
+    }
+}
+
+fn fixed_axis_clear<T: Copy + Eq + Hash + Send + Sync + 'static>(
+    mut fixed_axis: ResMut<FixedAxis<T>>,
+) {
+    fixed_axis.clear();
+}
@@ -1,13 +1,26 @@
+use std::hash::Hash;
+
 use bevy::prelude::*;
 
+use super::for_each_fixed_schedule;
+
 /// A base set (similar to [`CoreSet`]), but for fixed timestep systems.
 #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
 #[system_set(base)]
 pub enum FixedSet {
-    /// Runs before [`FixedSet::Update`].
+    /// Runs before all other fixed timestep systems. Used for bookkeeping such as taking
+    /// snapshots and latching resources for the tick.
+    First,
+    /// The copy of [`apply_system_buffers`] that runs immediately after `First`.
+    FirstFlush,
+    /// Runs before [`FixedSet::Update`]. [`FixedTick`](`super::FixedTick`) is incremented at the
+    /// start of this set.
     PreUpdate,
     /// The copy of [`apply_system_buffers`] that runs immediately after `PreUpdate`.
     PreUpdateFlush,
+    /// Applies state transitions for states added with
+    /// [`AddFixedState`](`super::AddFixedState`), running [`OnExit`] and [`OnEnter`] schedules.
+    StateTransitions,
     /// Responsible for doing most app logic.
     Update,
     /// The copy of [`apply_system_buffers`] that runs immediately after `Update`.
@@ -16,32 +29,120 @@ pub enum FixedSet {
     PostUpdate,
     /// The copy of [`apply_system_buffers`] that runs immediately after `PostUpdate`.
     PostUpdateFlush,
+    /// Runs after all other fixed timestep systems. Used for bookkeeping such as clearing inputs
+    /// at the end of the tick.
+    Last,
+    /// The copy of [`apply_system_buffers`] that runs immediately after `Last`.
+    LastFlush,
+}
+
+/// The copy of [`apply_system_buffers`] that runs immediately after a stage added with
+/// [`AddFixedStage`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+#[system_set(base)]
+pub struct FixedStageFlush<S: SystemSet + Clone + Eq + Hash>(pub S);
+
+/// A trait implemented by [`App`] to insert custom stages between the stages of [`FixedSet`].
+///
+/// Each stage is a base set followed by its own [`FixedStageFlush`], and is added to
+/// [`CoreSchedule::FixedUpdate`] and every schedule added with
+/// [`AddFixedSchedule`](`super::AddFixedSchedule`).
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::prelude::*;
+/// # use halia::fixed_timestep::{FixedSet, FixedStageFlush};
+/// #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+/// #[system_set(base)]
+/// pub struct Physics;
+///
+/// #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+/// #[system_set(base)]
+/// pub struct Collisions;
+///
+/// fn integrate() {}
+///
+/// fn resolve_collisions() {}
+///
+/// App::new()
+///     .add_plugins(HaliaPlugins)
+///     .add_fixed_stage(Physics, FixedSet::UpdateFlush, FixedSet::PostUpdate)
+///     .add_fixed_stage(Collisions, FixedStageFlush(Physics), FixedSet::PostUpdate)
+///     .add_system(integrate.in_base_set(Physics).in_schedule(CoreSchedule::FixedUpdate))
+///     .add_system(
+///         resolve_collisions
+///             .in_base_set(Collisions)
+///             .in_schedule(CoreSchedule::FixedUpdate),
+///     );
+/// ```
+pub trait AddFixedStage {
+    /// Add `stage` to every fixed schedule, running after `after` and before `before`, followed by
+    /// [`FixedStageFlush(stage)`](`FixedStageFlush`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stage` is not a base set.
+    fn add_fixed_stage<S: SystemSet + Clone + Eq + Hash>(
+        &mut self,
+        stage: S,
+        after: impl SystemSet + Clone,
+        before: impl SystemSet + Clone,
+    ) -> &mut Self;
+}
+
+impl AddFixedStage for App {
+    fn add_fixed_stage<S: SystemSet + Clone + Eq + Hash>(
+        &mut self,
+        stage: S,
+        after: impl SystemSet + Clone,
+        before: impl SystemSet + Clone,
+    ) -> &mut Self {
+        assert!(stage.is_base(), "fixed stage {stage:?} must be a base set");
+        for_each_fixed_schedule(self, move |schedule| {
+            schedule
+                .configure_set(stage.clone().after(after.clone()).before(before.clone()))
+                .configure_set(
+                    FixedStageFlush(stage.clone())
+                        .after(stage.clone())
+                        .before(before.clone()),
+                )
+                .add_system(apply_system_buffers.in_base_set(FixedStageFlush(stage.clone())));
+        });
+        self
+    }
 }
 
 pub(crate) struct FixedTimestepBaseSetPlugin;
 
 impl Plugin for FixedTimestepBaseSetPlugin {
     fn build(&self, app: &mut App) {
-        {
-            let Some(schedule) = app.get_schedule_mut(CoreSchedule::FixedUpdate) else {
-                warn!("halia_fixed_timestep relies on CoreSchedule::FixedUpdate, but it was not found");
-                return;
-            };
-            schedule
-                .set_default_base_set(FixedSet::Update)
-                .configure_set(FixedSet::PreUpdate.before(FixedSet::PreUpdateFlush))
-                .configure_set(FixedSet::PreUpdateFlush.before(FixedSet::Update))
-                .configure_set(FixedSet::Update.before(FixedSet::UpdateFlush))
-                .configure_set(FixedSet::UpdateFlush.before(FixedSet::PostUpdate))
-                .configure_set(FixedSet::PostUpdate.before(FixedSet::PostUpdateFlush));
+        if app.get_schedule(CoreSchedule::FixedUpdate).is_none() {
+            warn!("halia_fixed_timestep relies on CoreSchedule::FixedUpdate, but it was not found");
+            return;
         }
-        app.add_systems(
-            (
-                apply_system_buffers.in_base_set(FixedSet::PreUpdateFlush),
-                apply_system_buffers.in_base_set(FixedSet::UpdateFlush),
-                apply_system_buffers.in_base_set(FixedSet::PostUpdateFlush),
-            )
-                .in_schedule(CoreSchedule::FixedUpdate),
-        );
+        app.edit_schedule(CoreSchedule::FixedUpdate, add_fixed_base_sets);
     }
 }
+
+/// Configure [`FixedSet`] and its flushes in a fixed schedule.
+pub(crate) fn add_fixed_base_sets(schedule: &mut Schedule) {
+    schedule
+        .set_default_base_set(FixedSet::Update)
+        .configure_set(FixedSet::First.before(FixedSet::FirstFlush))
+        .configure_set(FixedSet::FirstFlush.before(FixedSet::PreUpdate))
+        .configure_set(FixedSet::PreUpdate.before(FixedSet::PreUpdateFlush))
+        .configure_set(FixedSet::PreUpdateFlush.before(FixedSet::StateTransitions))
+        .configure_set(FixedSet::StateTransitions.before(FixedSet::Update))
+        .configure_set(FixedSet::Update.before(FixedSet::UpdateFlush))
+        .configure_set(FixedSet::UpdateFlush.before(FixedSet::PostUpdate))
+        .configure_set(FixedSet::PostUpdate.before(FixedSet::PostUpdateFlush))
+        .configure_set(FixedSet::PostUpdateFlush.before(FixedSet::Last))
+        .configure_set(FixedSet::Last.before(FixedSet::LastFlush))
+        .add_systems((
+            apply_system_buffers.in_base_set(FixedSet::FirstFlush),
+            apply_system_buffers.in_base_set(FixedSet::PreUpdateFlush),
+            apply_system_buffers.in_base_set(FixedSet::UpdateFlush),
+            apply_system_buffers.in_base_set(FixedSet::PostUpdateFlush),
+            apply_system_buffers.in_base_set(FixedSet::LastFlush),
+        ));
+}
@@ -0,0 +1,323 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::schedule::BoxedScheduleLabel, prelude::*, time::fixed_timestep::run_fixed_update_schedule,
+};
+
+use super::AddFixedEvent;
+
+/// System set for the systems which apply [`FixedTimeControl`]. Runs in [`CoreSet::FixedUpdate`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub struct FixedTimeControlSystem;
+
+pub(crate) struct FixedTimestepControlPlugin;
+
+impl Plugin for FixedTimestepControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FixedTimeControl>()
+            .add_fixed_event::<FixedTimestepOverrun>()
+            .add_systems(
+                (
+                    fixed_time_control_drive.before(run_fixed_update_schedule),
+                    fixed_time_control_resume.after(run_fixed_update_schedule),
+                )
+                    .in_set(FixedTimeControlSystem)
+                    .in_base_set(CoreSet::FixedUpdate),
+            );
+    }
+}
+
+/// Controls how fast [`CoreSchedule::FixedUpdate`] runs, without changing [`FixedTime`].
+///
+/// Inputs, fixed events and timers are only consumed by ticks, so nothing is lost while the fixed
+/// schedule is slowed down or paused. Systems outside of the fixed schedule keep running every
+/// frame.
+///
+/// By default, as many ticks run each frame as needed to catch up with real time. After a hitch,
+/// this can cause the next frame to take even longer, spiraling until the app stops responding.
+/// To prevent this, limit the ticks per frame with [`FixedTimeControl::set_max_ticks_per_frame`]
+/// and choose what happens to the remaining time with [`FixedTimeControl::set_catch_up`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::prelude::*;
+/// fn debug_controls(
+///     mut fixed_time_control: ResMut<FixedTimeControl>,
+///     keys: Res<Input<KeyCode>>,
+/// ) {
+///     if keys.just_pressed(KeyCode::P) {
+///         fixed_time_control.toggle_pause();
+///     }
+///     if keys.just_pressed(KeyCode::N) {
+///         fixed_time_control.step(1);
+///     }
+///     if keys.just_pressed(KeyCode::S) {
+///         fixed_time_control.set_scale(0.25);
+///     }
+/// }
+///
+/// App::new().insert_resource(
+///     FixedTimeControl::default()
+///         .with_max_ticks_per_frame(Some(5))
+///         .with_catch_up(CatchUp::Drop),
+/// );
+/// # bevy::ecs::system::assert_is_system(debug_controls);
+/// ```
+#[derive(Clone, Debug, Resource)]
+pub struct FixedTimeControl {
+    scale: f32,
+    paused: bool,
+    steps: u32,
+    max_ticks_per_frame: Option<u32>,
+    catch_up: CatchUp,
+    suspended: Option<(Duration, Duration)>,
+}
+
+impl Default for FixedTimeControl {
+    fn default() -> Self {
+        Self {
+            scale: 1.,
+            paused: false,
+            steps: 0,
+            max_ticks_per_frame: None,
+            catch_up: CatchUp::default(),
+            suspended: None,
+        }
+    }
+}
+
+impl FixedTimeControl {
+    /// The rate at which time passes for the fixed schedule, where 1 is real time.
+    #[must_use]
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Set the rate at which time passes for the fixed schedule, where 1 is real time. Negative
+    /// values are treated as 0.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale.max(0.);
+    }
+
+    /// Returns `true` if the fixed schedule is paused.
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pause the fixed schedule. Time does not accumulate while paused.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resume the fixed schedule.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Pause the fixed schedule if it is running, otherwise resume it.
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Run `ticks` additional ticks during the next frame, even if paused.
+    pub fn step(&mut self, ticks: u32) {
+        self.steps = self.steps.saturating_add(ticks);
+    }
+
+    /// The number of ticks requested with [`FixedTimeControl::step`] which have not run yet.
+    #[must_use]
+    pub fn pending_steps(&self) -> u32 {
+        self.steps
+    }
+
+    /// The maximum number of ticks which run in a single frame, or [`None`] for no limit. Ticks
+    /// requested with [`FixedTimeControl::step`] are not limited.
+    #[must_use]
+    pub fn max_ticks_per_frame(&self) -> Option<u32> {
+        self.max_ticks_per_frame
+    }
+
+    /// Set the maximum number of ticks which run in a single frame, or [`None`] for no limit.
+    pub fn set_max_ticks_per_frame(&mut self, max_ticks_per_frame: Option<u32>) {
+        self.max_ticks_per_frame = max_ticks_per_frame;
+    }
+
+    /// Returns this [`FixedTimeControl`] with a new maximum number of ticks per frame.
+    #[must_use]
+    pub fn with_max_ticks_per_frame(mut self, max_ticks_per_frame: Option<u32>) -> Self {
+        self.set_max_ticks_per_frame(max_ticks_per_frame);
+        self
+    }
+
+    /// What happens to time which could not be simulated because of
+    /// [`FixedTimeControl::max_ticks_per_frame`].
+    #[must_use]
+    pub fn catch_up(&self) -> CatchUp {
+        self.catch_up
+    }
+
+    /// Set what happens to time which could not be simulated because of
+    /// [`FixedTimeControl::max_ticks_per_frame`].
+    pub fn set_catch_up(&mut self, catch_up: CatchUp) {
+        self.catch_up = catch_up;
+    }
+
+    /// Returns this [`FixedTimeControl`] with a new [`CatchUp`] policy.
+    #[must_use]
+    pub fn with_catch_up(mut self, catch_up: CatchUp) -> Self {
+        self.set_catch_up(catch_up);
+        self
+    }
+
+    /// Limit `delta` before it is added to `fixed_time`, following [`CatchUp::SlowDown`].
+    /// Returns the limited delta and the number of whole ticks discarded.
+    pub(crate) fn limit_delta(&self, delta: Duration, fixed_time: &FixedTime) -> (Duration, u64) {
+        let (CatchUp::SlowDown, Some(max_ticks)) = (self.catch_up, self.max_ticks_per_frame) else {
+            return (delta, 0);
+        };
+        let period = fixed_time.period;
+        let limited = delta.min(period.saturating_mul(max_ticks));
+        let accumulated = fixed_time.accumulated();
+        let dropped = whole_ticks(accumulated.saturating_add(delta), period)
+            - whole_ticks(accumulated.saturating_add(limited), period);
+        (limited, dropped)
+    }
+
+    fn is_real_time(&self) -> bool {
+        (self.scale - 1.).abs() < f32::EPSILON
+            && !self.paused
+            && self.steps == 0
+            && self.max_ticks_per_frame.is_none()
+    }
+}
+
+/// What happens to time which could not be simulated because of
+/// [`FixedTimeControl::max_ticks_per_frame`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum CatchUp {
+    /// Discard the remaining whole ticks. The fixed schedule falls behind real time, but recovers
+    /// immediately.
+    Drop,
+    /// Limit the time added each frame to [`FixedTimeControl::max_ticks_per_frame`] ticks,
+    /// discarding the rest, so the fixed schedule runs slower than real time while frames take too
+    /// long.
+    #[default]
+    SlowDown,
+    /// Keep the remaining time, running it over the following frames. Nothing is lost, but the
+    /// fixed schedule may take a while to catch up with real time.
+    CarryOver,
+}
+
+/// Sent when ticks are dropped because of [`FixedTimeControl::max_ticks_per_frame`], with either
+/// [`CatchUp::Drop`] or [`CatchUp::SlowDown`], for [`CoreSchedule::FixedUpdate`] and schedules
+/// added with [`AddFixedSchedule`](`super::AddFixedSchedule`).
+#[derive(Clone, Debug)]
+pub struct FixedTimestepOverrun {
+    /// The schedule which dropped ticks.
+    pub schedule: BoxedScheduleLabel,
+    /// The number of ticks which ran this frame.
+    pub ticks: u32,
+    /// The number of ticks which were dropped.
+    pub dropped: u64,
+}
+
+/// Stop [`run_fixed_update_schedule`] from running ticks this frame, so that an exclusive system
+/// in [`CoreSet::FixedUpdate`] can run them instead. [`FixedTime`] is restored afterwards.
+///
+/// Returns `false` if ticks have already been taken over this frame.
+pub(crate) fn suspend_fixed_update(world: &mut World) -> bool {
+    if is_fixed_update_suspended(world) {
+        return false;
+    }
+    let mut fixed_time = world.resource_mut::<FixedTime>();
+    let suspended = (fixed_time.period, fixed_time.accumulated());
+    fixed_time.period = Duration::MAX;
+    world.resource_mut::<FixedTimeControl>().suspended = Some(suspended);
+    true
+}
+
+/// Returns `true` if ticks have already been taken over this frame with [`suspend_fixed_update`].
+pub(crate) fn is_fixed_update_suspended(world: &World) -> bool {
+    world.resource::<FixedTimeControl>().suspended.is_some()
+}
+
+/// Discard the whole ticks accumulated in `fixed_time`, keeping the remainder, following
+/// [`CatchUp::Drop`]. Returns the number of ticks dropped.
+pub(crate) fn drop_fixed_ticks(fixed_time: &mut FixedTime) -> u64 {
+    let period = fixed_time.period;
+    let accumulated = fixed_time.accumulated();
+    let dropped = whole_ticks(accumulated, period);
+    if dropped > 0 {
+        *fixed_time = FixedTime::new(period);
+        fixed_time.tick(Duration::from_nanos(
+            u64::try_from(accumulated.as_nanos() % period.as_nanos()).unwrap_or(0),
+        ));
+    }
+    dropped
+}
+
+/// The number of whole ticks of `period` in `duration`.
+fn whole_ticks(duration: Duration, period: Duration) -> u64 {
+    if period.is_zero() {
+        return 0;
+    }
+    u64::try_from(duration.as_nanos() / period.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Run ticks accumulated in [`FixedTime`], up to `max_ticks`, returning the number of ticks run.
+fn run_fixed_update_ticks(world: &mut World, max_ticks: u32) -> u32 {
+    let mut ticks = 0;
+    while ticks < max_ticks && world.resource_mut::<FixedTime>().expend().is_ok() {
+        world.run_schedule(CoreSchedule::FixedUpdate);
+        ticks += 1;
+    }
+    ticks
+}
+
+fn fixed_time_control_drive(world: &mut World) {
+    let control = world.resource::<FixedTimeControl>();
+    if control.suspended.is_some()
+        || control.is_real_time()
+        || !world.contains_resource::<FixedTime>()
+    {
+        return;
+    }
+    let delta = match world.get_resource::<Time>() {
+        Some(time) if !control.paused => time.delta().mul_f64(f64::from(control.scale)),
+        _ => Duration::ZERO,
+    };
+    let (delta, mut dropped) = control.limit_delta(delta, world.resource::<FixedTime>());
+    let max_ticks = control.max_ticks_per_frame.unwrap_or(u32::MAX);
+    let catch_up = control.catch_up;
+    let steps = std::mem::take(&mut world.resource_mut::<FixedTimeControl>().steps);
+    world.resource_mut::<FixedTime>().tick(delta);
+    let ticks = run_fixed_update_ticks(world, max_ticks);
+    if catch_up == CatchUp::Drop {
+        dropped += drop_fixed_ticks(&mut world.resource_mut::<FixedTime>());
+    }
+    if dropped > 0 {
+        world.send_event(FixedTimestepOverrun {
+            schedule: Box::new(CoreSchedule::FixedUpdate),
+            ticks,
+            dropped,
+        });
+    }
+    for _ in 0..steps {
+        world.run_schedule(CoreSchedule::FixedUpdate);
+    }
+    suspend_fixed_update(world);
+}
+
+fn fixed_time_control_resume(
+    mut fixed_time_control: ResMut<FixedTimeControl>,
+    fixed_time: Option<ResMut<FixedTime>>,
+) {
+    let Some(mut fixed_time) = fixed_time else {
+        return;
+    };
+    if let Some((period, accumulated)) = fixed_time_control.suspended.take() {
+        *fixed_time = FixedTime::new(period);
+        fixed_time.tick(accumulated);
+    }
+}
@@ -0,0 +1,325 @@
+use std::time::Duration;
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticId, Diagnostics},
+    prelude::*,
+    time::fixed_timestep::run_fixed_update_schedule,
+    utils::Instant,
+};
+
+use super::{FixedSet, FixedTimeControlSystem};
+
+/// System set for the system which publishes [`FixedTimestepDiagnostics`]. Runs in
+/// [`CoreSet::FixedUpdate`], after all ticks for the frame have run.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub struct FixedTimestepDiagnosticsSystem;
+
+pub(crate) struct FixedTimestepDiagnosticsPlugin;
+
+impl Plugin for FixedTimestepDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FixedTimestepDiagnostics>()
+            .init_resource::<FixedTickTimings>()
+            .init_resource::<Diagnostics>()
+            .add_systems(
+                (
+                    fixed_tick_mark_start
+                        .no_default_base_set()
+                        .before(FixedSet::First),
+                    fixed_tick_mark::<1>
+                        .no_default_base_set()
+                        .after(FixedSet::FirstFlush)
+                        .before(FixedSet::PreUpdate),
+                    fixed_tick_mark::<2>
+                        .no_default_base_set()
+                        .after(FixedSet::PreUpdateFlush)
+                        .before(FixedSet::StateTransitions),
+                    fixed_tick_mark::<3>
+                        .no_default_base_set()
+                        .after(FixedSet::StateTransitions)
+                        .before(FixedSet::Update),
+                    fixed_tick_mark::<4>
+                        .no_default_base_set()
+                        .after(FixedSet::UpdateFlush)
+                        .before(FixedSet::PostUpdate),
+                    fixed_tick_mark::<5>
+                        .no_default_base_set()
+                        .after(FixedSet::PostUpdateFlush)
+                        .before(FixedSet::Last),
+                    fixed_tick_mark_end
+                        .no_default_base_set()
+                        .after(FixedSet::LastFlush),
+                )
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                fixed_timestep_diagnostics
+                    .in_set(FixedTimestepDiagnosticsSystem)
+                    .in_base_set(CoreSet::FixedUpdate)
+                    .after(run_fixed_update_schedule)
+                    .after(FixedTimeControlSystem),
+            );
+        let mut diagnostics = app.world.resource_mut::<Diagnostics>();
+        diagnostics.add(Diagnostic::new(
+            FixedTimestepDiagnostics::TICKS_PER_FRAME,
+            "fixed_ticks_per_frame",
+            20,
+        ));
+        for (id, name) in [
+            (FixedTimestepDiagnostics::OVERSTEP, "fixed_overstep"),
+            (
+                FixedTimestepDiagnostics::TICK_DURATION,
+                "fixed_tick_duration",
+            ),
+            (
+                FixedTimestepDiagnostics::WORST_TICK_DURATION,
+                "fixed_worst_tick_duration",
+            ),
+            (
+                FixedTimestepDiagnostics::FIRST_DURATION,
+                "fixed_first_duration",
+            ),
+            (
+                FixedTimestepDiagnostics::PRE_UPDATE_DURATION,
+                "fixed_pre_update_duration",
+            ),
+            (
+                FixedTimestepDiagnostics::STATE_TRANSITIONS_DURATION,
+                "fixed_state_transitions_duration",
+            ),
+            (
+                FixedTimestepDiagnostics::UPDATE_DURATION,
+                "fixed_update_duration",
+            ),
+            (
+                FixedTimestepDiagnostics::POST_UPDATE_DURATION,
+                "fixed_post_update_duration",
+            ),
+            (
+                FixedTimestepDiagnostics::LAST_DURATION,
+                "fixed_last_duration",
+            ),
+        ] {
+            diagnostics.add(Diagnostic::new(id, name, 20).with_suffix("ms"));
+        }
+    }
+}
+
+/// Measurements of the fixed schedule during the last frame.
+///
+/// The same measurements are published to Bevy's [`Diagnostics`], which keeps a history and can be
+/// logged with [`LogDiagnosticsPlugin`](`bevy::diagnostic::LogDiagnosticsPlugin`). Durations are
+/// published in milliseconds.
+///
+/// Durations are measured per tick, separately for each [`FixedSet`], and include the flush
+/// following each set. Stages added with [`AddFixedStage`](`super::AddFixedStage`) count towards
+/// the set before or after them. Frames which run no ticks keep the durations of the last frame
+/// which did.
+///
+/// Only [`CoreSchedule::FixedUpdate`] is measured. Schedules added with
+/// [`AddFixedSchedule`](`super::AddFixedSchedule`) run their own ticks, which are not counted.
+#[derive(Clone, Debug, Default, Resource)]
+pub struct FixedTimestepDiagnostics {
+    ticks: u32,
+    overstep: Duration,
+    average_tick: Duration,
+    worst_tick: Duration,
+    sets: [Duration; SETS],
+}
+
+impl FixedTimestepDiagnostics {
+    /// The number of ticks which ran each frame.
+    pub const TICKS_PER_FRAME: DiagnosticId =
+        DiagnosticId::from_u128(213_585_024_870_329_145_727_941_603_515_912_361_733);
+    /// The time accumulated towards the next tick at the end of each frame.
+    pub const OVERSTEP: DiagnosticId =
+        DiagnosticId::from_u128(95_437_165_318_627_813_392_402_129_470_268_715_251);
+    /// The average time taken to run a tick.
+    pub const TICK_DURATION: DiagnosticId =
+        DiagnosticId::from_u128(306_729_482_183_540_962_519_301_735_847_116_284_603);
+    /// The longest time taken to run a tick each frame.
+    pub const WORST_TICK_DURATION: DiagnosticId =
+        DiagnosticId::from_u128(47_806_353_215_964_197_108_655_743_982_530_117_921);
+    /// The average time taken to run [`FixedSet::First`].
+    pub const FIRST_DURATION: DiagnosticId =
+        DiagnosticId::from_u128(35_880_114_650_172_393_848_705_183_466_926_377_468);
+    /// The average time taken to run [`FixedSet::PreUpdate`].
+    pub const PRE_UPDATE_DURATION: DiagnosticId =
+        DiagnosticId::from_u128(180_257_339_764_014_852_931_562_078_309_478_652_177);
+    /// The average time taken to run [`FixedSet::StateTransitions`].
+    pub const STATE_TRANSITIONS_DURATION: DiagnosticId =
+        DiagnosticId::from_u128(297_461_250_882_714_639_057_148_325_790_681_042_316);
+    /// The average time taken to run [`FixedSet::Update`].
+    pub const UPDATE_DURATION: DiagnosticId =
+        DiagnosticId::from_u128(262_118_907_435_281_706_391_853_402_716_954_083_219);
+    /// The average time taken to run [`FixedSet::PostUpdate`].
+    pub const POST_UPDATE_DURATION: DiagnosticId =
+        DiagnosticId::from_u128(128_594_600_237_815_339_064_228_917_553_402_871_045);
+    /// The average time taken to run [`FixedSet::Last`].
+    pub const LAST_DURATION: DiagnosticId =
+        DiagnosticId::from_u128(71_245_923_108_446_580_937_214_690_352_867_509_134);
+
+    /// The number of ticks which ran during the last frame.
+    #[must_use]
+    pub fn ticks(&self) -> u32 {
+        self.ticks
+    }
+
+    /// The time accumulated towards the next tick at the end of the last frame.
+    #[must_use]
+    pub fn overstep(&self) -> Duration {
+        self.overstep
+    }
+
+    /// The average time taken to run a tick.
+    #[must_use]
+    pub fn average_tick(&self) -> Duration {
+        self.average_tick
+    }
+
+    /// The longest time taken to run a tick.
+    #[must_use]
+    pub fn worst_tick(&self) -> Duration {
+        self.worst_tick
+    }
+
+    /// The average time taken to run [`FixedSet::First`].
+    #[must_use]
+    pub fn first(&self) -> Duration {
+        self.sets[0]
+    }
+
+    /// The average time taken to run [`FixedSet::PreUpdate`].
+    #[must_use]
+    pub fn pre_update(&self) -> Duration {
+        self.sets[1]
+    }
+
+    /// The average time taken to run [`FixedSet::StateTransitions`].
+    #[must_use]
+    pub fn state_transitions(&self) -> Duration {
+        self.sets[2]
+    }
+
+    /// The average time taken to run [`FixedSet::Update`].
+    #[must_use]
+    pub fn update(&self) -> Duration {
+        self.sets[3]
+    }
+
+    /// The average time taken to run [`FixedSet::PostUpdate`].
+    #[must_use]
+    pub fn post_update(&self) -> Duration {
+        self.sets[4]
+    }
+
+    /// The average time taken to run [`FixedSet::Last`].
+    #[must_use]
+    pub fn last(&self) -> Duration {
+        self.sets[5]
+    }
+}
+
+/// The number of [`FixedSet`]s which are measured, ignoring flushes.
+const SETS: usize = 6;
+
+#[derive(Default, Resource)]
+struct FixedTickTimings {
+    marks: [Option<Instant>; SETS],
+    ticks: u32,
+    total: Duration,
+    worst: Duration,
+    sets: [Duration; SETS],
+}
+
+fn fixed_tick_mark_start(mut timings: ResMut<FixedTickTimings>) {
+    timings.marks = [None; SETS];
+    timings.marks[0] = Some(Instant::now());
+}
+
+fn fixed_tick_mark<const SET: usize>(mut timings: ResMut<FixedTickTimings>) {
+    timings.marks[SET] = Some(Instant::now());
+}
+
+fn fixed_tick_mark_end(mut timings: ResMut<FixedTickTimings>) {
+    let marks = timings.marks;
+    if marks.iter().any(Option::is_none) {
+        return;
+    }
+    let end = Instant::now();
+    let marks = marks.map(|mark| mark.unwrap_or(end));
+    let tick = end - marks[0];
+    timings.ticks += 1;
+    timings.total += tick;
+    timings.worst = timings.worst.max(tick);
+    for (set, start) in marks.iter().enumerate() {
+        let set_end = marks.get(set + 1).copied().unwrap_or(end);
+        timings.sets[set] += set_end - *start;
+    }
+}
+
+fn fixed_timestep_diagnostics(
+    mut fixed_timestep_diagnostics: ResMut<FixedTimestepDiagnostics>,
+    mut timings: ResMut<FixedTickTimings>,
+    mut diagnostics: ResMut<Diagnostics>,
+    fixed_time: Option<Res<FixedTime>>,
+) {
+    let timings = std::mem::take(timings.as_mut());
+    fixed_timestep_diagnostics.ticks = timings.ticks;
+    fixed_timestep_diagnostics.overstep = fixed_time
+        .map(|fixed_time| fixed_time.accumulated())
+        .unwrap_or_default();
+    if timings.ticks > 0 {
+        fixed_timestep_diagnostics.average_tick = timings.total / timings.ticks;
+        fixed_timestep_diagnostics.worst_tick = timings.worst;
+        fixed_timestep_diagnostics.sets = timings.sets.map(|set| set / timings.ticks);
+    }
+    diagnostics.add_measurement(FixedTimestepDiagnostics::TICKS_PER_FRAME, || {
+        f64::from(timings.ticks)
+    });
+    diagnostics.add_measurement(FixedTimestepDiagnostics::OVERSTEP, || {
+        milliseconds(fixed_timestep_diagnostics.overstep)
+    });
+    if timings.ticks > 0 {
+        for (id, duration) in [
+            (
+                FixedTimestepDiagnostics::TICK_DURATION,
+                fixed_timestep_diagnostics.average_tick,
+            ),
+            (
+                FixedTimestepDiagnostics::WORST_TICK_DURATION,
+                fixed_timestep_diagnostics.worst_tick,
+            ),
+            (
+                FixedTimestepDiagnostics::FIRST_DURATION,
+                fixed_timestep_diagnostics.first(),
+            ),
+            (
+                FixedTimestepDiagnostics::PRE_UPDATE_DURATION,
+                fixed_timestep_diagnostics.pre_update(),
+            ),
+            (
+                FixedTimestepDiagnostics::STATE_TRANSITIONS_DURATION,
+                fixed_timestep_diagnostics.state_transitions(),
+            ),
+            (
+                FixedTimestepDiagnostics::UPDATE_DURATION,
+                fixed_timestep_diagnostics.update(),
+            ),
+            (
+                FixedTimestepDiagnostics::POST_UPDATE_DURATION,
+                fixed_timestep_diagnostics.post_update(),
+            ),
+            (
+                FixedTimestepDiagnostics::LAST_DURATION,
+                fixed_timestep_diagnostics.last(),
+            ),
+        ] {
+            diagnostics.add_measurement(id, || milliseconds(duration));
+        }
+    }
+}
+
+fn milliseconds(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.
+}
@@ -1,6 +1,10 @@
 use std::marker::PhantomData;
 
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use super::{
+    add_fixed_schedule_local_default, for_each_fixed_schedule, FixedScheduleLocal, FixedTick,
+};
 
 /// A trait implemented by [`App`], similar to [`App::add_event`], which works properly with fixed
 /// timestep systems. Events will continue to work properly in non-fixed timestep systems as well.
@@ -15,22 +19,162 @@ use bevy::prelude::*;
 /// // after:
 /// App::new().add_fixed_event::<MyEvent>();
 /// ```
+///
+/// Events are kept until a frame in which the fixed schedule ran at least one tick, so each fixed
+/// timestep [`EventReader`] sees every event exactly once, even when a frame runs zero or several
+/// ticks. Since this can hold events for several frames, events can also be stamped with the tick
+/// they were sent in (see [`AddFixedEvent::add_ticked_fixed_event`]).
+///
+/// Every schedule added with [`AddFixedSchedule`](`super::AddFixedSchedule`) has its own events,
+/// which are swapped in while it runs and cleared after its own ticks. Events sent by its systems
+/// are only read by its systems, and it does not read events sent anywhere else.
 pub trait AddFixedEvent {
     /// Setup the application to manage events of type T (similar to [`App::add_event`]), but which
     /// also work in fixed timestep systems.
     fn add_fixed_event<T: Event>(&mut self) -> &mut Self;
+
+    /// Setup the application to manage events of type T, stamped with the [`FixedTick`] they were
+    /// sent in. Events are sent with [`FixedEventWriter<T>`] and read with
+    /// [`FixedEventReader<T>`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use halia::prelude::*;
+    /// pub struct Jump;
+    ///
+    /// fn send_jump(mut jump_events: FixedEventWriter<Jump>) {
+    ///     jump_events.send(Jump);
+    /// }
+    ///
+    /// fn jump(mut jump_events: FixedEventReader<Jump>) {
+    ///     // ignore jumps which were sent before the previous tick
+    ///     for _ in jump_events.iter_within(2) {
+    ///         // ...
+    ///     }
+    /// }
+    ///
+    /// App::new()
+    ///     .add_plugins(HaliaPlugins)
+    ///     .add_ticked_fixed_event::<Jump>()
+    ///     .add_system(send_jump)
+    ///     .add_system(jump.in_schedule(CoreSchedule::FixedUpdate));
+    /// ```
+    fn add_ticked_fixed_event<T: Event>(&mut self) -> &mut Self;
 }
 
 impl AddFixedEvent for App {
     fn add_fixed_event<T: Event>(&mut self) -> &mut Self {
         self.init_resource::<EventClearFlag<T>>()
             .init_resource::<Events<T>>()
-            .add_systems((fixed_events_clear_flag::<T>,).in_schedule(CoreSchedule::FixedUpdate))
             .add_systems((fixed_events_clear::<T>,).in_base_set(CoreSet::Last));
+        add_fixed_schedule_local_default::<EventClearFlag<T>>(self);
+        add_fixed_schedule_local_default::<Events<T>>(self);
+        for_each_fixed_schedule(self, add_fixed_events_clear_flag::<T>);
         self
     }
+
+    fn add_ticked_fixed_event<T: Event>(&mut self) -> &mut Self {
+        self.init_resource::<FixedTick>()
+            .add_fixed_event::<TickedEvent<T>>()
+    }
+}
+
+/// An event stamped with the [`FixedTick`] it was sent in (see
+/// [`AddFixedEvent::add_ticked_fixed_event`]).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TickedEvent<T> {
+    /// The [`FixedTick`] the event was sent in. Events sent outside of fixed timestep systems are
+    /// stamped with the last tick which ran.
+    pub tick: u64,
+    /// The event.
+    pub event: T,
+}
+
+/// Sends events stamped with the current [`FixedTick`] (see
+/// [`AddFixedEvent::add_ticked_fixed_event`]).
+#[derive(SystemParam)]
+pub struct FixedEventWriter<'w, T: Event> {
+    writer: EventWriter<'w, TickedEvent<T>>,
+    fixed_tick: Res<'w, FixedTick>,
 }
 
+impl<T: Event> FixedEventWriter<'_, T> {
+    /// Send an event, stamped with the current [`FixedTick`].
+    pub fn send(&mut self, event: T) {
+        self.writer.send(TickedEvent {
+            tick: self.fixed_tick.0,
+            event,
+        });
+    }
+
+    /// Send a list of events, all stamped with the current [`FixedTick`].
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = T>) {
+        let tick = self.fixed_tick.0;
+        self.writer
+            .send_batch(events.into_iter().map(|event| TickedEvent { tick, event }));
+    }
+}
+
+/// Reads events sent with [`FixedEventWriter<T>`], optionally filtering them by the tick they
+/// were sent in (see [`AddFixedEvent::add_ticked_fixed_event`]).
+///
+/// Like [`EventReader`], every event is only read once, including events which were filtered
+/// out.
+#[derive(SystemParam)]
+pub struct FixedEventReader<'w, 's, T: Event> {
+    reader: EventReader<'w, 's, TickedEvent<T>>,
+    fixed_tick: Res<'w, FixedTick>,
+}
+
+impl<T: Event> FixedEventReader<'_, '_, T> {
+    /// Iterate over every unread event, along with the tick it was sent in.
+    pub fn iter(&mut self) -> impl Iterator<Item = &TickedEvent<T>> {
+        self.reader.iter()
+    }
+
+    /// Iterate over unread events which were sent in `tick`.
+    pub fn iter_tick(&mut self, tick: u64) -> impl Iterator<Item = &T> {
+        self.reader
+            .iter()
+            .filter(move |ticked| ticked.tick == tick)
+            .map(|ticked| &ticked.event)
+    }
+
+    /// Iterate over unread events which were sent within the last `ticks` ticks, including the
+    /// current tick, so `iter_within(1)` only returns events sent during the current tick. Events
+    /// sent before this are skipped.
+    pub fn iter_within(&mut self, ticks: u64) -> impl Iterator<Item = &T> {
+        let current = self.fixed_tick.0;
+        self.reader
+            .iter()
+            .filter(move |ticked| ticked.tick.saturating_add(ticks) > current)
+            .map(|ticked| &ticked.event)
+    }
+
+    /// The number of unread events.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.reader.len()
+    }
+
+    /// Returns `true` if there are no unread events.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.reader.is_empty()
+    }
+
+    /// Mark every unread event as read.
+    pub fn clear(&mut self) {
+        self.reader.clear();
+    }
+}
+
+fn add_fixed_events_clear_flag<T: Event>(schedule: &mut Schedule) {
+    schedule.add_system(fixed_events_clear_flag::<T>);
+}
+
+/// Set once a fixed schedule has run, so its events are cleared at the end of the frame. Every
+/// schedule added with [`AddFixedSchedule`](`super::AddFixedSchedule`) has its own flag.
 #[derive(Resource)]
 struct EventClearFlag<T: Event> {
     clear: bool,
@@ -53,6 +197,21 @@ fn fixed_events_clear_flag<T: Event>(mut event_clear_flag: ResMut<EventClearFlag
 fn fixed_events_clear<T: Event>(
     mut event_clear_flag: ResMut<EventClearFlag<T>>,
     mut fixed_events: ResMut<Events<T>>,
+    mut local_event_clear_flags: ResMut<FixedScheduleLocal<EventClearFlag<T>>>,
+    mut local_fixed_events: ResMut<FixedScheduleLocal<Events<T>>>,
+) {
+    clear_fixed_events(&mut event_clear_flag, &mut fixed_events);
+    for (event_clear_flag, fixed_events) in local_event_clear_flags
+        .iter_mut()
+        .zip(local_fixed_events.iter_mut())
+    {
+        clear_fixed_events(event_clear_flag, fixed_events);
+    }
+}
+
+fn clear_fixed_events<T: Event>(
+    event_clear_flag: &mut EventClearFlag<T>,
+    fixed_events: &mut Events<T>,
 ) {
     if event_clear_flag.clear {
         fixed_events.update();
@@ -1,10 +1,19 @@
-use std::hash::Hash;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::{Deref, DerefMut},
+};
 
 use bevy::{input::InputSystem, prelude::*, reflect::Reflect};
 
-use super::FixedSet;
+use super::{
+    add_fixed_schedule_local, for_each_fixed_schedule, FixedScheduleLocal, FixedSet, FixedTick,
+};
 
 /// System set for updating fixed timestep input state.
+///
+/// In [`CoreSet::PreUpdate`], this set copies [`Input`] state into [`FixedInput`]. In
+/// [`FixedSet::Last`], it clears the just pressed and just released state after each tick.
 #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
 pub struct FixedInputSystem;
 
@@ -13,23 +22,57 @@ pub trait AddFixedInput {
     /// Add a [`FixedInput`] version of [`Input`] for T. This is called automatically for
     /// [`KeyCode`], [`ScanCode`], [`MouseButton`], and [`GamepadButton`].
     fn add_fixed_input<T: Copy + Eq + Hash + Send + Sync + 'static>(&mut self) -> &mut Self;
+
+    /// Set how many ticks presses of T are remembered for [`FixedInput::just_pressed_within`].
+    /// Defaults to [`FixedInput::DEFAULT_BUFFER_DEPTH`], and is never less than 1.
+    fn set_fixed_input_buffer_depth<T: Copy + Eq + Hash + Send + Sync + 'static>(
+        &mut self,
+        buffer_depth: u32,
+    ) -> &mut Self;
 }
 
 impl AddFixedInput for App {
     fn add_fixed_input<T: Copy + Eq + Hash + Send + Sync + 'static>(&mut self) -> &mut Self {
-        self.init_resource::<FixedInput<T>>().add_systems((
-            fixed_input_update::<T>
-                .in_base_set(CoreSet::PreUpdate)
-                .after(InputSystem),
-            fixed_input_clear::<T>
-                .in_schedule(CoreSchedule::FixedUpdate)
-                .in_set(FixedInputSystem)
-                .in_base_set(FixedSet::PostUpdate),
-        ));
+        self.init_resource::<FixedTick>()
+            .init_resource::<FixedInput<T>>()
+            .add_system(
+                fixed_input_update::<T>
+                    .in_set(FixedInputSystem)
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(InputSystem),
+            );
+        for_each_fixed_schedule(self, add_fixed_input_clear::<T>);
+        add_fixed_schedule_local::<FixedInput<T>>(self);
+        self
+    }
+
+    fn set_fixed_input_buffer_depth<T: Copy + Eq + Hash + Send + Sync + 'static>(
+        &mut self,
+        buffer_depth: u32,
+    ) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(FixedInput::<T>::default)
+            .set_buffer_depth(buffer_depth);
+        if let Some(mut locals) = self
+            .world
+            .get_resource_mut::<FixedScheduleLocal<FixedInput<T>>>()
+        {
+            for fixed_input in locals.iter_mut() {
+                fixed_input.set_buffer_depth(buffer_depth);
+            }
+        }
         self
     }
 }
 
+fn add_fixed_input_clear<T: Copy + Eq + Hash + Send + Sync + 'static>(schedule: &mut Schedule) {
+    schedule.add_system(
+        fixed_input_clear::<T>
+            .in_set(FixedInputSystem)
+            .in_base_set(FixedSet::Last),
+    );
+}
+
 pub(crate) struct FixedTimestepInputPlugin;
 
 impl Plugin for FixedTimestepInputPlugin {
@@ -43,30 +86,342 @@ impl Plugin for FixedTimestepInputPlugin {
 
 /// A fixed timestep version of [`Input`]. Implements [`Deref`] and [`DerefMut`] for the internal
 /// [`Input`] member, so see that struct for documentation.
-#[derive(Clone, Deref, DerefMut, Debug, Reflect, Resource)]
+///
+/// Presses are also buffered for a number of ticks (see [`FixedInput::just_pressed_within`]),
+/// which is useful for input leniency such as jump buffering.
+///
+/// Press and release times are tracked in ticks, allowing hold durations
+/// ([`FixedInput::held_ticks`]) and multi-taps ([`FixedInput::just_multi_tapped`]) to behave the
+/// same regardless of frame rate.
+#[derive(Clone, Debug, Reflect, Resource)]
 #[reflect(Default)]
-pub struct FixedInput<T: Copy + Eq + Hash + Send + Sync + 'static>(Input<T>);
+pub struct FixedInput<T: Copy + Eq + Hash + Send + Sync + 'static> {
+    input: Input<T>,
+    #[reflect(ignore)]
+    buffer: HashMap<T, u64>,
+    buffer_depth: u32,
+    tick: u64,
+    #[reflect(ignore)]
+    press_ticks: HashMap<T, VecDeque<u64>>,
+    #[reflect(ignore)]
+    release_ticks: HashMap<T, u64>,
+    #[reflect(ignore)]
+    latched: Vec<InputLatch<T>>,
+}
 
 impl<T: Copy + Eq + Hash + Send + Sync + 'static> Default for FixedInput<T> {
     fn default() -> Self {
-        Self(Input::default())
+        Self {
+            input: Input::default(),
+            buffer: HashMap::new(),
+            buffer_depth: Self::DEFAULT_BUFFER_DEPTH,
+            tick: 0,
+            press_ticks: HashMap::new(),
+            release_ticks: HashMap::new(),
+            latched: vec![],
+        }
+    }
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static> Deref for FixedInput<T> {
+    type Target = Input<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.input
+    }
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static> DerefMut for FixedInput<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.input
+    }
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static> FixedInput<T> {
+    /// The default number of ticks a press is buffered for.
+    pub const DEFAULT_BUFFER_DEPTH: u32 = 8;
+
+    /// The maximum number of taps [`FixedInput::just_multi_tapped`] can detect.
+    pub const MAX_TAPS: usize = 8;
+
+    /// Returns `true` if `input` was pressed during the current tick or the previous `ticks - 1`
+    /// ticks, and the press has not been [consumed](`FixedInput::consume`).
+    ///
+    /// `just_pressed_within(input, 1)` behaves like [`Input::just_pressed`]. Presses are only
+    /// remembered for [`FixedInput::buffer_depth`] ticks.
+    #[must_use]
+    pub fn just_pressed_within(&self, input: T, ticks: u32) -> bool {
+        self.buffer
+            .get(&input)
+            .is_some_and(|press_tick| ticks_between(*press_tick, self.tick) < ticks)
+    }
+
+    /// Consume a buffered press of `input`, so that it is no longer reported by
+    /// [`FixedInput::just_pressed_within`] or [`Input::just_pressed`]. Returns `true` if there was
+    /// a press to consume.
+    pub fn consume(&mut self, input: T) -> bool {
+        let buffered = self.buffer.remove(&input).is_some();
+        let just_pressed = self.input.clear_just_pressed(input);
+        buffered || just_pressed
+    }
+
+    /// The number of ticks presses are buffered for.
+    #[must_use]
+    pub fn buffer_depth(&self) -> u32 {
+        self.buffer_depth
+    }
+
+    /// Set the number of ticks presses are buffered for. Clamped to at least 1, so that a press is
+    /// always reported by [`FixedInput::just_pressed_within`] during the tick it happens.
+    pub fn set_buffer_depth(&mut self, buffer_depth: u32) {
+        let buffer_depth = buffer_depth.max(1);
+        self.buffer_depth = buffer_depth;
+        let tick = self.tick;
+        self.buffer
+            .retain(|_, press_tick| ticks_between(*press_tick, tick) < buffer_depth);
+    }
+
+    /// The [`FixedTick`] of the last tick this input was advanced past, or 0 before the first tick.
+    /// Presses and releases are stamped with this value, so they belong to the next tick.
+    #[must_use]
+    pub fn current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// The number of ticks `input` has been held for, or [`None`] if it is not pressed.
+    ///
+    /// Returns `Some(0)` on the tick the input was pressed.
+    #[must_use]
+    pub fn held_ticks(&self, input: T) -> Option<u32> {
+        if !self.input.pressed(input) {
+            return None;
+        }
+        self.press_ticks
+            .get(&input)
+            .and_then(|press_ticks| press_ticks.back())
+            .map(|press_tick| ticks_between(*press_tick, self.tick))
+    }
+
+    /// The number of ticks since `input` was last released, or [`None`] if it has never been
+    /// released.
+    ///
+    /// Returns `Some(0)` on the tick the input was released.
+    #[must_use]
+    pub fn released_ticks_ago(&self, input: T) -> Option<u32> {
+        self.release_ticks
+            .get(&input)
+            .map(|release_tick| ticks_between(*release_tick, self.tick))
+    }
+
+    /// The number of ticks `input` was held for before it was last released, or [`None`] if it
+    /// has never been released. Useful for charged actions which trigger on release.
+    #[must_use]
+    pub fn released_after_ticks(&self, input: T) -> Option<u32> {
+        let release_tick = *self.release_ticks.get(&input)?;
+        self.press_ticks
+            .get(&input)?
+            .iter()
+            .rev()
+            .find(|press_tick| **press_tick <= release_tick)
+            .map(|press_tick| ticks_between(*press_tick, release_tick))
+    }
+
+    /// Returns `true` on the tick `input` is pressed, if this press and the `taps - 1` presses
+    /// before it each happened within `window` ticks of the previous press.
+    ///
+    /// `taps` is limited to [`FixedInput::MAX_TAPS`].
+    #[must_use]
+    pub fn just_multi_tapped(&self, input: T, taps: usize, window: u32) -> bool {
+        if taps == 0 || taps > Self::MAX_TAPS {
+            return false;
+        }
+        let Some(press_ticks) = self.press_ticks.get(&input) else {
+            return false;
+        };
+        if press_ticks.len() < taps || press_ticks.back() != Some(&self.tick) {
+            return false;
+        }
+        press_ticks
+            .iter()
+            .rev()
+            .zip(press_ticks.iter().rev().skip(1))
+            .take(taps - 1)
+            .all(|(press_tick, previous_press_tick)| {
+                ticks_between(*previous_press_tick, *press_tick) <= window
+            })
+    }
+
+    /// Returns `true` on the tick `input` is pressed for the second time within `window` ticks.
+    #[must_use]
+    pub fn just_double_tapped(&self, input: T, window: u32) -> bool {
+        self.just_multi_tapped(input, 2, window)
+    }
+
+    /// Returns `true` on the tick `input` is pressed for the third time, with each press within
+    /// `window` ticks of the previous.
+    #[must_use]
+    pub fn just_triple_tapped(&self, input: T, window: u32) -> bool {
+        self.just_multi_tapped(input, 3, window)
+    }
+
+    fn latch_press(&mut self, input: T) {
+        self.input.press(input);
+        self.buffer.insert(input, self.tick);
+        let press_ticks = self.press_ticks.entry(input).or_default();
+        if press_ticks.len() == Self::MAX_TAPS {
+            press_ticks.pop_front();
+        }
+        press_ticks.push_back(self.tick);
+        self.latched.push(InputLatch::Press(input));
+    }
+
+    fn latch_release(&mut self, input: T) {
+        self.input.release(input);
+        self.release_ticks.insert(input, self.tick);
+        self.latched.push(InputLatch::Release(input));
+    }
+
+    /// Presses and releases latched since the last tick, in the order they happened.
+    #[must_use]
+    pub fn latched(&self) -> &[InputLatch<T>] {
+        &self.latched
+    }
+
+    /// Apply a press or release as if it came from [`Input`].
+    pub fn latch(&mut self, latch: InputLatch<T>) {
+        match latch {
+            InputLatch::Press(input) => self.latch_press(input),
+            InputLatch::Release(input) => self.latch_release(input),
+        }
+    }
+
+    /// Capture the complete state of this input, including buffered presses and tick history.
+    #[must_use]
+    pub fn snapshot(&self) -> FixedInputSnapshot<T> {
+        FixedInputSnapshot {
+            pressed: self.input.get_pressed().copied().collect(),
+            just_pressed: self.input.get_just_pressed().copied().collect(),
+            just_released: self.input.get_just_released().copied().collect(),
+            buffer: self.buffer.clone(),
+            buffer_depth: self.buffer_depth,
+            tick: self.tick,
+            press_ticks: self.press_ticks.clone(),
+            release_ticks: self.release_ticks.clone(),
+        }
+    }
+
+    /// Restore the state captured by [`FixedInput::snapshot`].
+    pub fn restore(&mut self, snapshot: FixedInputSnapshot<T>) {
+        // Input has no way to set just pressed and just released directly, so replay the presses
+        // and releases needed to produce them, then clear the extra just pressed state
+        self.input.reset_all();
+        for input in &snapshot.pressed {
+            self.input.press(*input);
+        }
+        for input in &snapshot.just_released {
+            if self.input.pressed(*input) {
+                self.input.release(*input);
+                self.input.press(*input);
+            } else {
+                self.input.press(*input);
+                self.input.release(*input);
+            }
+        }
+        let just_pressed: Vec<T> = self.input.get_just_pressed().copied().collect();
+        for input in just_pressed {
+            if !snapshot.just_pressed.contains(&input) {
+                self.input.clear_just_pressed(input);
+            }
+        }
+        self.buffer = snapshot.buffer;
+        self.buffer_depth = snapshot.buffer_depth;
+        self.tick = snapshot.tick;
+        self.press_ticks = snapshot.press_ticks;
+        self.release_ticks = snapshot.release_ticks;
+        self.latched.clear();
+    }
+
+    /// Clear the just pressed and just released state at the end of `tick`, which press and
+    /// release ages are measured from.
+    pub(crate) fn advance(&mut self, tick: u64) {
+        self.input.clear();
+        self.latched.clear();
+        self.tick = tick;
+        let buffer_depth = self.buffer_depth;
+        self.buffer
+            .retain(|_, press_tick| ticks_between(*press_tick, tick) < buffer_depth);
+    }
+}
+
+/// A press or release latched into a [`FixedInput`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum InputLatch<T> {
+    /// The input was pressed.
+    Press(T),
+    /// The input was released.
+    Release(T),
+}
+
+/// The complete state of a [`FixedInput`], used to restore it exactly.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct FixedInputSnapshot<T: Copy + Eq + Hash + Send + Sync + 'static> {
+    pressed: Vec<T>,
+    just_pressed: Vec<T>,
+    just_released: Vec<T>,
+    buffer: HashMap<T, u64>,
+    buffer_depth: u32,
+    tick: u64,
+    press_ticks: HashMap<T, VecDeque<u64>>,
+    release_ticks: HashMap<T, u64>,
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn ticks_between(from: u64, to: u64) -> u32 {
+    to.saturating_sub(from).min(u64::from(u32::MAX)) as u32
+}
+
+/// Update `input` to match the `pressed` and `just_pressed` sets of a frame, pressing inputs
+/// which became pressed and releasing inputs which are no longer pressed.
+pub(crate) fn sync_input<T: Copy + Eq + Hash + Send + Sync + 'static>(
+    input: &mut Input<T>,
+    pressed: &HashSet<T>,
+    just_pressed: &HashSet<T>,
+) {
+    input.clear();
+    // an input may be pressed and released within a single frame
+    for value in pressed.iter().chain(just_pressed) {
+        input.press(*value);
+    }
+    let released: Vec<T> = input
+        .get_pressed()
+        .filter(|value| !pressed.contains(value))
+        .copied()
+        .collect();
+    for value in released {
+        input.release(value);
     }
 }
 
 fn fixed_input_update<T: Copy + Eq + Hash + Send + Sync + 'static>(
     mut fixed_input: ResMut<FixedInput<T>>,
+    mut locals: ResMut<FixedScheduleLocal<FixedInput<T>>>,
     input: Res<Input<T>>,
 ) {
-    for pressed in input.get_just_pressed() {
-        fixed_input.press(*pressed);
-    }
-    for released in input.get_just_released() {
-        fixed_input.release(*released);
+    for fixed_input in std::iter::once(fixed_input.as_mut()).chain(locals.iter_mut()) {
+        for pressed in input.get_just_pressed() {
+            fixed_input.latch_press(*pressed);
+        }
+        for released in input.get_just_released() {
+            fixed_input.latch_release(*released);
+        }
     }
 }
 
 fn fixed_input_clear<T: Copy + Eq + Hash + Send + Sync + 'static>(
     mut fixed_input: ResMut<FixedInput<T>>,
+    fixed_tick: Res<FixedTick>,
 ) {
-    fixed_input.clear();
+    fixed_input.advance(fixed_tick.0);
 }
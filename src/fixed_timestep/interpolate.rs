@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+
+use crate::transform2::{Transform2, Transform2System};
+
+use super::{FixedSet, FixedTransformSystem};
+
+/// System set for [`InterpolatedTransform2`] systems.
+///
+/// Exists in both the main schedule and [`CoreSchedule::FixedUpdate`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub struct InterpolateSystem;
+
+pub(crate) struct FixedTimestepInterpolatePlugin;
+
+impl Plugin for FixedTimestepInterpolatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems((
+            interpolated_transform2_restore
+                .in_set(InterpolateSystem)
+                .in_base_set(CoreSet::First),
+            interpolated_transform2_blend
+                .in_set(InterpolateSystem)
+                .in_base_set(CoreSet::PostUpdate)
+                .before(Transform2System::Transform2Propagate),
+            interpolated_transform2_capture
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .in_set(InterpolateSystem)
+                .in_base_set(FixedSet::PostUpdate)
+                .before(FixedTransformSystem::Transform2Propagate),
+        ));
+    }
+}
+
+/// Smooths the rendered [`Transform2`] of an entity moved by fixed timestep systems.
+///
+/// The [`Transform2`] of each tick is remembered, and during [`CoreSet::PostUpdate`] the entity is
+/// rendered between the previous and current tick according to how far [`FixedTime`] has
+/// accumulated towards the next tick. The true [`Transform2`] is restored during
+/// [`CoreSet::First`], so systems always see the value set by the last tick.
+///
+/// Rendering lags up to one tick behind. To move an entity without interpolating from its previous
+/// position, call [`InterpolatedTransform2::teleport`] in the same tick.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::prelude::*;
+/// fn spawn(mut commands: Commands) {
+///     commands.spawn((
+///         SpatialBundle::default(),
+///         Transform2::default(),
+///         InterpolatedTransform2::default(),
+///     ));
+/// }
+///
+/// fn respawn(mut query: Query<(&mut Transform2, &mut InterpolatedTransform2)>) {
+///     for (mut transform2, mut interpolated) in &mut query {
+///         transform2.translation = Vec2::ZERO;
+///         interpolated.teleport();
+///     }
+/// }
+/// # bevy::ecs::system::assert_is_system(spawn);
+/// # bevy::ecs::system::assert_is_system(respawn);
+/// ```
+#[derive(Clone, Component, Copy, Debug)]
+pub struct InterpolatedTransform2 {
+    previous: Transform2,
+    current: Transform2,
+    snap: bool,
+}
+
+impl Default for InterpolatedTransform2 {
+    fn default() -> Self {
+        Self {
+            previous: Transform2::IDENTITY,
+            current: Transform2::IDENTITY,
+            snap: true,
+        }
+    }
+}
+
+impl InterpolatedTransform2 {
+    /// Skip interpolation for the current tick, so the entity is rendered at its new
+    /// [`Transform2`] immediately.
+    pub fn teleport(&mut self) {
+        self.snap = true;
+    }
+
+    /// The [`Transform2`] of the previous tick.
+    #[must_use]
+    pub fn previous(&self) -> Transform2 {
+        self.previous
+    }
+
+    /// The [`Transform2`] of the current tick.
+    #[must_use]
+    pub fn current(&self) -> Transform2 {
+        self.current
+    }
+
+    /// The interpolated [`Transform2`], `t` ticks between the previous and current tick.
+    #[must_use]
+    pub fn interpolate(&self, t: f32) -> Transform2 {
+        self.previous.lerp(self.current, t)
+    }
+}
+
+fn interpolated_transform2_capture(mut query: Query<(&Transform2, &mut InterpolatedTransform2)>) {
+    for (transform2, mut interpolated) in &mut query {
+        interpolated.previous = if interpolated.snap {
+            *transform2
+        } else {
+            interpolated.current
+        };
+        interpolated.current = *transform2;
+        interpolated.snap = false;
+    }
+}
+
+fn interpolated_transform2_blend(
+    mut query: Query<(&mut Transform2, &InterpolatedTransform2)>,
+    fixed_time: Option<Res<FixedTime>>,
+) {
+    let Some(fixed_time) = fixed_time else {
+        return;
+    };
+    let t = (fixed_time.accumulated().as_secs_f32() / fixed_time.period.as_secs_f32()).min(1.);
+    for (mut transform2, interpolated) in &mut query {
+        if !interpolated.snap {
+            *transform2 = interpolated.interpolate(t);
+        }
+    }
+}
+
+fn interpolated_transform2_restore(mut query: Query<(&mut Transform2, &InterpolatedTransform2)>) {
+    for (mut transform2, interpolated) in &mut query {
+        if !interpolated.snap {
+            *transform2 = interpolated.current;
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::mem;
+
+use bevy::{prelude::*, time::fixed_timestep::run_fixed_update_schedule};
+
+use super::{
+    add_fixed_schedule_local, for_each_fixed_schedule, FixedScheduleLocal, FixedSet,
+    FixedTimeControlSystem,
+};
+
+/// System set for accumulating resources registered with
+/// [`AddFixedLatch::add_fixed_latch_with`]. Runs in [`CoreSet::FixedUpdate`], before any ticks
+/// run.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub struct FixedLatchSystem;
+
+pub(crate) struct FixedTimestepLatchPlugin;
+
+impl Plugin for FixedTimestepLatchPlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_set(
+            FixedLatchSystem
+                .in_base_set(CoreSet::FixedUpdate)
+                .before(run_fixed_update_schedule)
+                .before(FixedTimeControlSystem),
+        );
+    }
+}
+
+/// A trait implemented by [`App`] allowing fixed timestep systems to read frame rate resources
+/// which stay the same for a whole tick.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::prelude::*;
+/// #[derive(Clone, Default, Resource)]
+/// struct WindowSize(Vec2);
+///
+/// #[derive(Clone, Default, Resource)]
+/// struct Recoil(Vec2);
+///
+/// fn aim(window_size: Res<Fixed<WindowSize>>, recoil: Res<Fixed<Recoil>>) {
+///     // ...
+/// }
+///
+/// App::new()
+///     .add_plugins(HaliaPlugins)
+///     .init_resource::<WindowSize>()
+///     .init_resource::<Recoil>()
+///     .add_fixed_latch::<WindowSize>()
+///     .add_fixed_latch_with::<Recoil>(|total, recoil| total.0 += recoil.0)
+///     .add_system(aim.in_schedule(CoreSchedule::FixedUpdate));
+/// ```
+pub trait AddFixedLatch {
+    /// Copy `R` into [`Fixed<R>`] at the start of each tick, during [`FixedSet::First`].
+    ///
+    /// [`Fixed<R>`] is only inserted once `R` exists.
+    fn add_fixed_latch<R: Resource + Clone>(&mut self) -> &mut Self;
+
+    /// Combine the value of `R` from every frame since the last tick using `reduce`, and hand the
+    /// result to the next tick as [`Fixed<R>`]. Useful for values which accumulate, such as
+    /// movement deltas.
+    ///
+    /// Each frame's value is reduced into [`R::default`](`Default::default`) before any ticks
+    /// run, so when a frame runs several ticks, only the first tick sees the frame's value, and
+    /// when a frame runs no ticks, its value is kept for the next tick.
+    fn add_fixed_latch_with<R: Resource + Clone + Default>(
+        &mut self,
+        reduce: fn(&mut R, &R),
+    ) -> &mut Self;
+}
+
+impl AddFixedLatch for App {
+    fn add_fixed_latch<R: Resource + Clone>(&mut self) -> &mut Self {
+        if let Some(resource) = self.world.get_resource::<R>() {
+            let fixed = Fixed(resource.clone());
+            self.insert_resource(fixed);
+        }
+        for_each_fixed_schedule(self, add_fixed_latch_copy::<R>);
+        self
+    }
+
+    fn add_fixed_latch_with<R: Resource + Clone + Default>(
+        &mut self,
+        reduce: fn(&mut R, &R),
+    ) -> &mut Self {
+        self.init_resource::<Fixed<R>>()
+            .init_resource::<FixedLatchAccumulator<R>>()
+            .insert_resource(FixedLatchReduce(reduce))
+            .add_system(fixed_latch_reduce::<R>.in_set(FixedLatchSystem));
+        for_each_fixed_schedule(self, add_fixed_latch_take::<R>);
+        add_fixed_schedule_local::<FixedLatchAccumulator<R>>(self);
+        self
+    }
+}
+
+fn add_fixed_latch_copy<R: Resource + Clone>(schedule: &mut Schedule) {
+    schedule.add_system(fixed_latch_copy::<R>.in_base_set(FixedSet::First));
+}
+
+fn add_fixed_latch_take<R: Resource + Default>(schedule: &mut Schedule) {
+    schedule.add_system(fixed_latch_take::<R>.in_base_set(FixedSet::First));
+}
+
+/// The value of a frame rate resource `R`, latched for fixed timestep systems (see
+/// [`AddFixedLatch`]).
+#[derive(Clone, Copy, Debug, Default, Deref, DerefMut, Eq, Hash, PartialEq, Resource)]
+pub struct Fixed<R>(pub R);
+
+#[derive(Clone, Default, Resource)]
+struct FixedLatchAccumulator<R>(R);
+
+#[derive(Resource)]
+struct FixedLatchReduce<R>(fn(&mut R, &R));
+
+fn fixed_latch_copy<R: Resource + Clone>(
+    mut commands: Commands,
+    resource: Option<Res<R>>,
+    fixed: Option<ResMut<Fixed<R>>>,
+) {
+    let Some(resource) = resource else {
+        return;
+    };
+    if let Some(mut fixed) = fixed {
+        fixed.0.clone_from(&resource);
+    } else {
+        commands.insert_resource(Fixed(resource.clone()));
+    }
+}
+
+fn fixed_latch_reduce<R: Resource + Clone + Default>(
+    mut accumulator: ResMut<FixedLatchAccumulator<R>>,
+    mut locals: ResMut<FixedScheduleLocal<FixedLatchAccumulator<R>>>,
+    reduce: Res<FixedLatchReduce<R>>,
+    resource: Option<Res<R>>,
+) {
+    let Some(resource) = resource else {
+        return;
+    };
+    for accumulator in std::iter::once(accumulator.as_mut()).chain(locals.iter_mut()) {
+        (reduce.0)(&mut accumulator.0, &resource);
+    }
+}
+
+fn fixed_latch_take<R: Resource + Default>(
+    mut accumulator: ResMut<FixedLatchAccumulator<R>>,
+    mut fixed: ResMut<Fixed<R>>,
+) {
+    fixed.0 = mem::take(&mut accumulator.0);
+}
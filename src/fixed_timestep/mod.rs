@@ -1,9 +1,28 @@
 //! Makes fixed timestep systems more usable.
 //!
-//! - Fixes events being dropped (see [`AddFixedEvent`]).
+//! - Fixes events being dropped, and stamps events with the tick they were sent in (see
+//!   [`AddFixedEvent`]).
 //! - Fixes inputs being dropped or double counted (see [`FixedInput`]).
-//! - Adds a base set (see [`FixedSet`]).
-//! - Fixes [`GlobalTransform`] not being updated.
+//! - Buffers inputs for a number of ticks (see [`FixedInput::just_pressed_within`]).
+//! - Adds analog axes which are consistent between ticks (see [`FixedAxis`]).
+//! - Fixes typed text being dropped or duplicated (see [`FixedTextInput`]).
+//! - Latches frame rate resources so they stay the same for a whole tick (see [`AddFixedLatch`]).
+//! - Maps inputs to user defined actions (see [`ActionMap`]).
+//! - Recognizes input sequences and chords (see [`AddInputSequences`]).
+//! - Assigns keyboard layouts and gamepads to players for local multiplayer (see [`PlayerSlots`]).
+//! - Adds a base set, which can be extended with custom stages (see [`FixedSet`] and
+//!   [`AddFixedStage`]).
+//! - Applies state transitions between ticks (see [`AddFixedState`]).
+//! - Counts ticks and measures time in whole ticks (see [`FixedTick`], [`FixedTimer`] and
+//!   [`FixedStopwatch`]).
+//! - Generates random numbers deterministically (see [`FixedRng`]).
+//! - Fixes [`GlobalTransform`] not being updated, only visiting entities which changed (see
+//!   [`FixedTransformSystem`] and [`NoFixedPropagation`]).
+//! - Scales, pauses and single-steps the fixed schedule, and limits ticks per frame (see
+//!   [`FixedTimeControl`]).
+//! - Adds fixed schedules which run at their own rate (see [`AddFixedSchedule`]).
+//! - Measures ticks per frame and tick durations (see [`FixedTimestepDiagnostics`]).
+//! - Smooths rendering between ticks (see [`InterpolatedTransform2`]).
 //!
 //! Fixed timestep systems are any system added to the [`CoreSchedule::FixedUpdate`] schedule.
 //!
@@ -18,23 +37,65 @@ pub struct FixedTimestepPlugin;
 
 impl Plugin for FixedTimestepPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugin(FixedTimestepBaseSetPlugin)
+        app.add_plugin(FixedTimestepSchedulesPlugin)
+            .add_plugin(FixedTimestepBaseSetPlugin)
             .add_plugin(FixedTimestepInputPlugin)
-            .add_plugin(FixedTimestepPropagatePlugin);
+            .add_plugin(FixedTimestepAxisPlugin)
+            .add_plugin(FixedTimestepTimePlugin)
+            .add_plugin(FixedTimestepRngPlugin)
+            .add_plugin(FixedTimestepControlPlugin)
+            .add_plugin(FixedTimestepLatchPlugin)
+            .add_plugin(FixedTimestepDiagnosticsPlugin)
+            .add_plugin(FixedTimestepPropagatePlugin)
+            .add_plugin(FixedTimestepInterpolatePlugin);
     }
 }
 
+mod action;
+mod axis;
 mod base_set;
+mod control;
+mod diagnostics;
 mod events;
 mod input;
+mod interpolate;
+mod latch;
+mod players;
+mod rng;
+mod schedules;
+mod sequence;
+mod state;
+mod text;
+mod time;
 mod transform;
 
+pub use action::*;
+pub use axis::*;
 pub use base_set::*;
+pub use control::*;
+pub use diagnostics::*;
 pub use events::*;
 pub use input::*;
+pub use interpolate::*;
+pub use latch::*;
+pub use players::*;
+pub use rng::*;
+pub use schedules::*;
+pub use sequence::*;
+pub use state::*;
+pub use text::*;
+pub use time::*;
 pub use transform::*;
 
 #[doc(hidden)]
 pub mod prelude {
-    pub use super::{AddFixedEvent, FixedInput};
+    pub use super::{
+        ActionMap, AddActionMap, AddFixedEvent, AddFixedLatch, AddFixedSchedule, AddFixedStage,
+        AddFixedState, AddFixedTextInput, AddInputSequences, AddPlayerSlots, CatchUp, Fixed,
+        FixedAxis, FixedDuration, FixedEventReader, FixedEventWriter, FixedInput, FixedRng,
+        FixedRngStream, FixedStopwatch, FixedTextInput, FixedTick, FixedTimeControl, FixedTimer,
+        FixedTimestepOverrun, InputSequence, InputSequenceEvent, InputSequences,
+        InterpolatedTransform2, KeyboardLayout, MouseAxis, NoFixedPropagation, PlayerButton,
+        PlayerDevice, PlayerSlotEvent, PlayerSlots, SequenceStep, TextEdit,
+    };
 }
@@ -0,0 +1,435 @@
+use std::collections::HashSet;
+
+use bevy::{
+    input::{
+        gamepad::{GamepadConnection, GamepadConnectionEvent},
+        InputSystem,
+    },
+    prelude::*,
+};
+
+use super::{sync_input, AddFixedEvent, AddFixedInput, FixedInput, FixedInputSystem};
+
+/// System set for updating [`PlayerSlots`] and player input.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub struct PlayerSlotsSystem;
+
+/// A trait implemented by [`App`] adding local multiplayer, where each player is assigned a
+/// keyboard layout or a gamepad (see [`PlayerSlots`]).
+///
+/// Each player's input is available in [`Input<PlayerButton>`] for regular systems and
+/// [`FixedInput<PlayerButton>`] for fixed timestep systems, which can be viewed one player at a
+/// time with [`FixedInput::player`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::prelude::*;
+/// fn jump(player_input: Res<FixedInput<PlayerButton>>, player_slots: Res<PlayerSlots>) {
+///     for (player, _) in player_slots.players() {
+///         if player_input.player(player).just_pressed(GamepadButtonType::South) {
+///             // ...
+///         }
+///     }
+/// }
+///
+/// App::new()
+///     .add_plugins(HaliaPlugins)
+///     .add_player_slots()
+///     .insert_resource(
+///         PlayerSlots::new(4)
+///             .with_player(PlayerDevice::Keyboard(KeyboardLayout::wasd()))
+///             .with_player(PlayerDevice::Keyboard(KeyboardLayout::arrows())),
+///     )
+///     .add_system(jump.in_schedule(CoreSchedule::FixedUpdate));
+/// ```
+pub trait AddPlayerSlots {
+    /// Add [`PlayerSlots`], along with a frame rate [`Input`] and a [`FixedInput`] for
+    /// [`PlayerButton`], and [`PlayerSlotEvent`] as a fixed event.
+    fn add_player_slots(&mut self) -> &mut Self;
+}
+
+impl AddPlayerSlots for App {
+    fn add_player_slots(&mut self) -> &mut Self {
+        self.init_resource::<PlayerSlots>()
+            .init_resource::<Input<PlayerButton>>()
+            .add_fixed_event::<PlayerSlotEvent>()
+            .add_system(
+                player_slots_update
+                    .in_set(PlayerSlotsSystem)
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(InputSystem)
+                    .before(FixedInputSystem),
+            )
+            .add_fixed_input::<PlayerButton>();
+        self
+    }
+}
+
+/// A button pressed by a player. Keyboard layouts are mapped to gamepad buttons, so every
+/// player uses the same buttons regardless of their device.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct PlayerButton {
+    /// The player's slot in [`PlayerSlots`].
+    pub player: usize,
+    /// The button.
+    pub button: GamepadButtonType,
+}
+
+impl PlayerButton {
+    /// Instantiate a new [`PlayerButton`].
+    #[must_use]
+    pub fn new(player: usize, button: GamepadButtonType) -> Self {
+        Self { player, button }
+    }
+}
+
+/// Maps keys to gamepad buttons, allowing a player to use part of a shared keyboard.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct KeyboardLayout {
+    bindings: Vec<(KeyCode, GamepadButtonType)>,
+}
+
+impl KeyboardLayout {
+    /// Create an empty [`KeyboardLayout`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The left half of the keyboard: WASD for the d-pad, and Space, Left Shift, Q and E for the
+    /// face buttons.
+    #[must_use]
+    pub fn wasd() -> Self {
+        Self::new()
+            .with_binding(KeyCode::W, GamepadButtonType::DPadUp)
+            .with_binding(KeyCode::A, GamepadButtonType::DPadLeft)
+            .with_binding(KeyCode::S, GamepadButtonType::DPadDown)
+            .with_binding(KeyCode::D, GamepadButtonType::DPadRight)
+            .with_binding(KeyCode::Space, GamepadButtonType::South)
+            .with_binding(KeyCode::LShift, GamepadButtonType::East)
+            .with_binding(KeyCode::Q, GamepadButtonType::West)
+            .with_binding(KeyCode::E, GamepadButtonType::North)
+    }
+
+    /// The right half of the keyboard: the arrow keys for the d-pad, and Return, Right Shift,
+    /// Right Control and Slash for the face buttons.
+    #[must_use]
+    pub fn arrows() -> Self {
+        Self::new()
+            .with_binding(KeyCode::Up, GamepadButtonType::DPadUp)
+            .with_binding(KeyCode::Left, GamepadButtonType::DPadLeft)
+            .with_binding(KeyCode::Down, GamepadButtonType::DPadDown)
+            .with_binding(KeyCode::Right, GamepadButtonType::DPadRight)
+            .with_binding(KeyCode::Return, GamepadButtonType::South)
+            .with_binding(KeyCode::RShift, GamepadButtonType::East)
+            .with_binding(KeyCode::RControl, GamepadButtonType::West)
+            .with_binding(KeyCode::Slash, GamepadButtonType::North)
+    }
+
+    /// Returns this [`KeyboardLayout`] with an additional binding from `key_code` to `button`.
+    #[must_use]
+    pub fn with_binding(mut self, key_code: KeyCode, button: GamepadButtonType) -> Self {
+        self.bindings.push((key_code, button));
+        self
+    }
+
+    /// The keys in this layout and the buttons they are mapped to.
+    #[must_use]
+    pub fn bindings(&self) -> &[(KeyCode, GamepadButtonType)] {
+        &self.bindings
+    }
+}
+
+/// A device owned by a player in [`PlayerSlots`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PlayerDevice {
+    /// Part of the keyboard.
+    Keyboard(KeyboardLayout),
+    /// A gamepad.
+    Gamepad(Gamepad),
+}
+
+/// Sent when a player joins or leaves [`PlayerSlots`], including joins and leaves caused by
+/// gamepads connecting and disconnecting.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PlayerSlotEvent {
+    /// A player joined, using `device`.
+    Joined {
+        /// The player's slot.
+        player: usize,
+        /// The player's device.
+        device: PlayerDevice,
+    },
+    /// A player left, which was using `device`.
+    Left {
+        /// The player's slot.
+        player: usize,
+        /// The player's device.
+        device: PlayerDevice,
+    },
+}
+
+/// Assigns devices to a fixed number of player slots for local multiplayer (see
+/// [`AddPlayerSlots`]).
+///
+/// Gamepads join the first free slot when they connect, and leave when they disconnect. This can
+/// be disabled with [`PlayerSlots::set_auto_join_gamepads`]. Keyboard layouts must be joined
+/// manually.
+#[derive(Clone, Debug, Resource)]
+pub struct PlayerSlots {
+    slots: Vec<Option<PlayerDevice>>,
+    auto_join_gamepads: bool,
+}
+
+impl Default for PlayerSlots {
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+impl PlayerSlots {
+    /// Create [`PlayerSlots`] for up to `max_players` players.
+    #[must_use]
+    pub fn new(max_players: usize) -> Self {
+        Self {
+            slots: vec![None; max_players],
+            auto_join_gamepads: true,
+        }
+    }
+
+    /// Returns these [`PlayerSlots`] with a player joined using `device`.
+    #[must_use]
+    pub fn with_player(mut self, device: PlayerDevice) -> Self {
+        self.join(device);
+        self
+    }
+
+    /// Returns these [`PlayerSlots`] with automatic joining of gamepads enabled or disabled.
+    #[must_use]
+    pub fn with_auto_join_gamepads(mut self, auto_join_gamepads: bool) -> Self {
+        self.auto_join_gamepads = auto_join_gamepads;
+        self
+    }
+
+    /// The maximum number of players.
+    #[must_use]
+    pub fn max_players(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// The number of players who have joined.
+    #[must_use]
+    pub fn player_count(&self) -> usize {
+        self.slots.iter().flatten().count()
+    }
+
+    /// Returns `true` if every slot has a player.
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.slots.iter().all(|slot| slot.is_some())
+    }
+
+    /// Join a player using `device` to the first free slot, returning the slot. Returns [`None`]
+    /// if every slot is taken, or `device` already belongs to a player.
+    pub fn join(&mut self, device: PlayerDevice) -> Option<usize> {
+        if self.player(&device).is_some() {
+            return None;
+        }
+        let player = self.slots.iter().position(|slot| slot.is_none())?;
+        self.slots[player] = Some(device);
+        Some(player)
+    }
+
+    /// Remove `player`, returning the device they were using.
+    pub fn leave(&mut self, player: usize) -> Option<PlayerDevice> {
+        self.slots.get_mut(player)?.take()
+    }
+
+    /// The device used by `player`, or [`None`] if the slot is free.
+    #[must_use]
+    pub fn device(&self, player: usize) -> Option<&PlayerDevice> {
+        self.slots.get(player)?.as_ref()
+    }
+
+    /// The player using `device`, or [`None`] if it does not belong to a player.
+    #[must_use]
+    pub fn player(&self, device: &PlayerDevice) -> Option<usize> {
+        self.slots
+            .iter()
+            .position(|slot| slot.as_ref() == Some(device))
+    }
+
+    /// Iterate over every player who has joined, along with their device.
+    pub fn players(&self) -> impl Iterator<Item = (usize, &PlayerDevice)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(player, slot)| Some((player, slot.as_ref()?)))
+    }
+
+    /// Returns `true` if gamepads join when they connect.
+    #[must_use]
+    pub fn auto_join_gamepads(&self) -> bool {
+        self.auto_join_gamepads
+    }
+
+    /// Set whether gamepads join when they connect. Gamepads always leave when they disconnect.
+    pub fn set_auto_join_gamepads(&mut self, auto_join_gamepads: bool) {
+        self.auto_join_gamepads = auto_join_gamepads;
+    }
+}
+
+impl FixedInput<PlayerButton> {
+    /// A view of the input of a single player.
+    #[must_use]
+    pub fn player(&self, player: usize) -> PlayerInput<'_> {
+        PlayerInput {
+            input: self,
+            player,
+        }
+    }
+}
+
+/// The fixed timestep input of a single player, returned by [`FixedInput::player`].
+#[derive(Clone, Copy, Debug)]
+pub struct PlayerInput<'a> {
+    input: &'a FixedInput<PlayerButton>,
+    player: usize,
+}
+
+impl PlayerInput<'_> {
+    /// The player's slot in [`PlayerSlots`].
+    #[must_use]
+    pub fn player(&self) -> usize {
+        self.player
+    }
+
+    /// See [`Input::pressed`].
+    #[must_use]
+    pub fn pressed(&self, button: GamepadButtonType) -> bool {
+        self.input.pressed(self.button(button))
+    }
+
+    /// See [`Input::just_pressed`].
+    #[must_use]
+    pub fn just_pressed(&self, button: GamepadButtonType) -> bool {
+        self.input.just_pressed(self.button(button))
+    }
+
+    /// See [`Input::just_released`].
+    #[must_use]
+    pub fn just_released(&self, button: GamepadButtonType) -> bool {
+        self.input.just_released(self.button(button))
+    }
+
+    /// See [`FixedInput::just_pressed_within`].
+    #[must_use]
+    pub fn just_pressed_within(&self, button: GamepadButtonType, ticks: u32) -> bool {
+        self.input.just_pressed_within(self.button(button), ticks)
+    }
+
+    /// See [`FixedInput::held_ticks`].
+    #[must_use]
+    pub fn held_ticks(&self, button: GamepadButtonType) -> Option<u32> {
+        self.input.held_ticks(self.button(button))
+    }
+
+    /// See [`FixedInput::released_ticks_ago`].
+    #[must_use]
+    pub fn released_ticks_ago(&self, button: GamepadButtonType) -> Option<u32> {
+        self.input.released_ticks_ago(self.button(button))
+    }
+
+    /// Iterate over every button the player is pressing.
+    pub fn get_pressed(&self) -> impl Iterator<Item = GamepadButtonType> + '_ {
+        self.input
+            .get_pressed()
+            .filter(|player_button| player_button.player == self.player)
+            .map(|player_button| player_button.button)
+    }
+
+    fn button(&self, button: GamepadButtonType) -> PlayerButton {
+        PlayerButton::new(self.player, button)
+    }
+}
+
+fn player_slots_update(
+    mut player_slots: ResMut<PlayerSlots>,
+    mut player_input: ResMut<Input<PlayerButton>>,
+    mut connection_events: EventReader<GamepadConnectionEvent>,
+    mut slot_events: EventWriter<PlayerSlotEvent>,
+    mut previous_slots: Local<Vec<Option<PlayerDevice>>>,
+    key_codes: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+) {
+    for connection_event in &mut connection_events {
+        let device = PlayerDevice::Gamepad(connection_event.gamepad);
+        match connection_event.connection {
+            GamepadConnection::Connected(_) => {
+                if player_slots.auto_join_gamepads {
+                    player_slots.join(device);
+                }
+            }
+            GamepadConnection::Disconnected => {
+                if let Some(player) = player_slots.player(&device) {
+                    player_slots.leave(player);
+                }
+            }
+        }
+    }
+
+    // compare against the last frame, so joins and leaves made through the resource are also sent
+    if player_slots.is_changed() {
+        previous_slots.resize(player_slots.slots.len(), None);
+        for (player, (previous_slot, slot)) in previous_slots
+            .iter_mut()
+            .zip(player_slots.slots.iter())
+            .enumerate()
+        {
+            if previous_slot == slot {
+                continue;
+            }
+            if let Some(device) = previous_slot.take() {
+                slot_events.send(PlayerSlotEvent::Left { player, device });
+            }
+            if let Some(device) = slot.clone() {
+                slot_events.send(PlayerSlotEvent::Joined { player, device });
+            }
+            *previous_slot = slot.clone();
+        }
+        previous_slots.truncate(player_slots.slots.len());
+    }
+
+    let mut pressed = HashSet::new();
+    let mut just_pressed = HashSet::new();
+    for (player, device) in player_slots.players() {
+        match device {
+            PlayerDevice::Keyboard(keyboard_layout) => {
+                for (key_code, button) in &keyboard_layout.bindings {
+                    let player_button = PlayerButton::new(player, *button);
+                    if key_codes.pressed(*key_code) {
+                        pressed.insert(player_button);
+                    }
+                    if key_codes.just_pressed(*key_code) {
+                        just_pressed.insert(player_button);
+                    }
+                }
+            }
+            PlayerDevice::Gamepad(gamepad) => {
+                for gamepad_button in gamepad_buttons.get_pressed() {
+                    if gamepad_button.gamepad == *gamepad {
+                        pressed.insert(PlayerButton::new(player, gamepad_button.button_type));
+                    }
+                }
+                for gamepad_button in gamepad_buttons.get_just_pressed() {
+                    if gamepad_button.gamepad == *gamepad {
+                        just_pressed.insert(PlayerButton::new(player, gamepad_button.button_type));
+                    }
+                }
+            }
+        }
+    }
+    sync_input(&mut player_input, &pressed, &just_pressed);
+}
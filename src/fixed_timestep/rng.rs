@@ -0,0 +1,237 @@
+use std::ops::{Deref, DerefMut, Range};
+
+use bevy::prelude::*;
+
+use super::{FixedSet, FixedTick, FixedTickSystem};
+
+/// System set for reseeding [`FixedRng`] at the start of each tick.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub struct FixedRngSystem;
+
+pub(crate) struct FixedTimestepRngPlugin;
+
+impl Plugin for FixedTimestepRngPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FixedRng>().add_system(
+            fixed_rng_update
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .in_set(FixedRngSystem)
+                .in_base_set(FixedSet::PreUpdate)
+                .after(FixedTickSystem),
+        );
+    }
+}
+
+/// A deterministic random number generator for fixed timestep systems.
+///
+/// At the start of each tick of [`CoreSchedule::FixedUpdate`], just after [`FixedTick`] is
+/// incremented, the generator is reseeded from its seed and the [`FixedTick`], so the numbers
+/// drawn during a tick only depend on the seed, the tick, and the order of draws within that
+/// tick. Draws made outside of the fixed schedule do not
+/// affect later ticks, and replays or rollbacks reproduce the same numbers without saving any
+/// generator state.
+///
+/// To stop systems from disturbing each other, draw from [`FixedRng::stream`] instead, which is
+/// independent of other keys and of draws from [`FixedRng`] itself. Streams for entities should
+/// be keyed on an id which stays the same across despawns and rollbacks, such as
+/// `RollbackId` from `halia_rollback`, rather than [`Entity`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::prelude::*;
+/// #[derive(Component)]
+/// struct Enemy {
+///     id: u64,
+/// }
+///
+/// fn wander(mut enemy_query: Query<(&Enemy, &mut Transform2)>, fixed_rng: Res<FixedRng>) {
+///     for (enemy, mut transform) in enemy_query.iter_mut() {
+///         let mut rng = fixed_rng.stream(enemy.id);
+///         transform.translation.x += rng.range_f32(-1.0..1.0);
+///         transform.translation.y += rng.range_f32(-1.0..1.0);
+///     }
+/// }
+///
+/// App::new()
+///     .add_plugins(HaliaPlugins)
+///     .insert_resource(FixedRng::new(1234))
+///     .add_system(wander.in_schedule(CoreSchedule::FixedUpdate));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Resource)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct FixedRng {
+    seed: u64,
+    tick_seed: u64,
+    stream: FixedRngStream,
+}
+
+impl Default for FixedRng {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Deref for FixedRng {
+    type Target = FixedRngStream;
+
+    fn deref(&self) -> &Self::Target {
+        &self.stream
+    }
+}
+
+impl DerefMut for FixedRng {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.stream
+    }
+}
+
+impl FixedRng {
+    /// Instantiate a new [`FixedRng`] with the given seed.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        let mut fixed_rng = Self {
+            seed,
+            tick_seed: 0,
+            stream: FixedRngStream::default(),
+        };
+        fixed_rng.reseed(0);
+        fixed_rng
+    }
+
+    /// The seed which every tick is derived from.
+    #[must_use]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Set the seed which every tick is derived from. Takes full effect from the next tick.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.reseed(0);
+    }
+
+    /// A generator for the current tick which is independent of every other key, and of draws
+    /// from [`FixedRng`] itself. Requesting the same key twice during a tick returns the same
+    /// generator.
+    #[must_use]
+    pub fn stream(&self, key: u64) -> FixedRngStream {
+        FixedRngStream::new(mix(self.tick_seed ^ mix(key.wrapping_add(GOLDEN_GAMMA))))
+    }
+
+    fn reseed(&mut self, tick: u64) {
+        self.tick_seed = mix(mix(self.seed).wrapping_add(tick));
+        self.stream = FixedRngStream::new(self.tick_seed);
+    }
+}
+
+/// A fast, seedable random number generator (`SplitMix64`).
+///
+/// Usually obtained from [`FixedRng`], but can also be stored in a component to give an entity a
+/// sequence of numbers which continues across ticks.
+#[derive(Clone, Component, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct FixedRngStream {
+    state: u64,
+}
+
+impl FixedRngStream {
+    /// Instantiate a new [`FixedRngStream`] with the given seed.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// A uniformly distributed `u64`.
+    pub fn u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        mix(self.state)
+    }
+
+    /// A uniformly distributed `u32`.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn u32(&mut self) -> u32 {
+        (self.u64() >> 32) as u32
+    }
+
+    /// A uniformly distributed `f32` in `0.0..1.0`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn f32(&mut self) -> f32 {
+        (self.u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// A uniformly distributed `f64` in `0.0..1.0`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn f64(&mut self) -> f64 {
+        (self.u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// `true` or `false` with equal probability.
+    pub fn bool(&mut self) -> bool {
+        self.u64() >> 63 == 1
+    }
+
+    /// `true` with the given probability, from 0 to 1.
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.f32() < probability
+    }
+
+    /// A uniformly distributed `u32` in `range`. Returns `range.start` if `range` is empty.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn range_u32(&mut self, range: Range<u32>) -> u32 {
+        let span = u64::from(range.end.saturating_sub(range.start));
+        range.start + ((u64::from(self.u32()) * span) >> 32) as u32
+    }
+
+    /// A uniformly distributed `i32` in `range`. Returns `range.start` if `range` is empty.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss
+    )]
+    pub fn range_i32(&mut self, range: Range<i32>) -> i32 {
+        let span = i64::from(range.end)
+            .saturating_sub(i64::from(range.start))
+            .max(0) as u64;
+        (i64::from(range.start) + ((u64::from(self.u32()) * span) >> 32) as i64) as i32
+    }
+
+    /// A uniformly distributed `f32` in `range`.
+    pub fn range_f32(&mut self, range: Range<f32>) -> f32 {
+        range.start + (range.end - range.start) * self.f32()
+    }
+
+    /// A uniformly chosen element of `slice`, or [`None`] if it is empty.
+    pub fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+        slice.get(self.index(slice.len())?)
+    }
+
+    /// Shuffle `slice` in place.
+    pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            if let Some(j) = self.index(i + 1) {
+                slice.swap(i, j);
+            }
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn index(&mut self, len: usize) -> Option<usize> {
+        if len == 0 {
+            return None;
+        }
+        let len = u64::try_from(len).ok()?;
+        Some(((u128::from(self.u64()) * u128::from(len)) >> 64) as usize)
+    }
+}
+
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn fixed_rng_update(mut fixed_rng: ResMut<FixedRng>, fixed_tick: Res<FixedTick>) {
+    fixed_rng.reseed(fixed_tick.0);
+}
@@ -0,0 +1,296 @@
+use std::{mem, sync::Arc, time::Duration};
+
+use bevy::{
+    ecs::schedule::{BoxedScheduleLabel, ScheduleLabel, Schedules},
+    prelude::*,
+};
+
+use super::{
+    add_fixed_base_sets, drop_fixed_ticks, CatchUp, FixedTimeControl, FixedTimeControlSystem,
+    FixedTimestepOverrun,
+};
+
+/// System set for the system which runs schedules added with [`AddFixedSchedule`]. Runs in
+/// [`CoreSet::FixedUpdate`], after [`FixedTimeControlSystem`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub struct FixedSchedulesSystem;
+
+pub(crate) struct FixedTimestepSchedulesPlugin;
+
+impl Plugin for FixedTimestepSchedulesPlugin {
+    fn build(&self, app: &mut App) {
+        app.world
+            .get_resource_or_insert_with(FixedSchedules::default);
+        app.add_system(
+            fixed_schedules_run
+                .in_set(FixedSchedulesSystem)
+                .in_base_set(CoreSet::FixedUpdate)
+                .after(FixedTimeControlSystem),
+        );
+    }
+}
+
+/// A trait implemented by [`App`] allowing additional fixed timestep schedules, each running at
+/// its own rate.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
+/// # use halia::prelude::*;
+/// #[derive(Clone, Debug, Eq, Hash, PartialEq, ScheduleLabel)]
+/// pub struct AiUpdate;
+///
+/// fn think() {}
+///
+/// App::new()
+///     .add_plugins(HaliaPlugins)
+///     .add_fixed_schedule(AiUpdate, Duration::from_millis(100))
+///     .add_system(think.in_schedule(AiUpdate));
+/// ```
+pub trait AddFixedSchedule {
+    /// Add a schedule which runs every `period`, independently of [`CoreSchedule::FixedUpdate`].
+    ///
+    /// The schedule gets its own [`FixedSet`](`super::FixedSet`) base sets and flushes,
+    /// [`FixedTick`](`super::FixedTick`), [`FixedInput`](`super::FixedInput`) and
+    /// [`FixedAxis`](`super::FixedAxis`) state, fixed events, and transform propagation.
+    /// [`FixedTimeControl`] scale, pause, ticks per frame and catch-up policy apply to every fixed
+    /// schedule, while steps, as well as [`InputSequences`](`super::InputSequences`) and replays,
+    /// only apply to [`CoreSchedule::FixedUpdate`].
+    fn add_fixed_schedule(&mut self, label: impl ScheduleLabel, period: Duration) -> &mut Self;
+}
+
+impl AddFixedSchedule for App {
+    fn add_fixed_schedule(&mut self, label: impl ScheduleLabel, period: Duration) -> &mut Self {
+        let label: BoxedScheduleLabel = Box::new(label);
+        edit_fixed_schedule(self, &*label, &add_fixed_base_sets);
+        let (setups, locals) = {
+            let mut fixed_schedules = self
+                .world
+                .get_resource_or_insert_with(FixedSchedules::default);
+            fixed_schedules.schedules.push(FixedScheduleEntry {
+                label: label.clone(),
+                fixed_time: FixedTime::new(period),
+            });
+            (
+                fixed_schedules.setups.clone(),
+                fixed_schedules.locals.clone(),
+            )
+        };
+        for setup in setups {
+            edit_fixed_schedule(self, &*label, &*setup);
+        }
+        for local in locals {
+            local(self);
+        }
+        self
+    }
+}
+
+/// The schedules added with [`AddFixedSchedule`].
+#[derive(Default, Resource)]
+pub struct FixedSchedules {
+    schedules: Vec<FixedScheduleEntry>,
+    setups: Vec<Arc<FixedScheduleSetup>>,
+    locals: Vec<fn(&mut App)>,
+    swaps: Vec<fn(&mut World, usize)>,
+}
+
+type FixedScheduleSetup = dyn Fn(&mut Schedule) + Send + Sync;
+
+struct FixedScheduleEntry {
+    label: BoxedScheduleLabel,
+    fixed_time: FixedTime,
+}
+
+impl FixedSchedules {
+    /// The period of a schedule, or [`None`] if it was not added with [`AddFixedSchedule`].
+    #[must_use]
+    pub fn period(&self, label: impl ScheduleLabel) -> Option<Duration> {
+        self.get(&label)
+            .map(|fixed_schedule| fixed_schedule.fixed_time.period)
+    }
+
+    /// Set the period of a schedule. Returns `false` if it was not added with
+    /// [`AddFixedSchedule`].
+    pub fn set_period(&mut self, label: impl ScheduleLabel, period: Duration) -> bool {
+        let Some(fixed_schedule) = self.get_mut(&label) else {
+            return false;
+        };
+        fixed_schedule.fixed_time.period = period;
+        true
+    }
+
+    /// The time accumulated towards the next tick of a schedule, or [`None`] if it was not added
+    /// with [`AddFixedSchedule`].
+    #[must_use]
+    pub fn accumulated(&self, label: impl ScheduleLabel) -> Option<Duration> {
+        self.get(&label)
+            .map(|fixed_schedule| fixed_schedule.fixed_time.accumulated())
+    }
+
+    fn get(&self, label: &dyn ScheduleLabel) -> Option<&FixedScheduleEntry> {
+        self.schedules
+            .iter()
+            .find(|fixed_schedule| &*fixed_schedule.label == label)
+    }
+
+    fn get_mut(&mut self, label: &dyn ScheduleLabel) -> Option<&mut FixedScheduleEntry> {
+        self.schedules
+            .iter_mut()
+            .find(|fixed_schedule| &*fixed_schedule.label == label)
+    }
+}
+
+/// Call `setup` for [`CoreSchedule::FixedUpdate`] and every schedule added with
+/// [`AddFixedSchedule`], including those added later.
+pub(crate) fn for_each_fixed_schedule(
+    app: &mut App,
+    setup: impl Fn(&mut Schedule) + Send + Sync + 'static,
+) {
+    let setup: Arc<FixedScheduleSetup> = Arc::new(setup);
+    let labels: Vec<BoxedScheduleLabel> = {
+        let mut fixed_schedules = app
+            .world
+            .get_resource_or_insert_with(FixedSchedules::default);
+        fixed_schedules.setups.push(setup.clone());
+        fixed_schedules
+            .schedules
+            .iter()
+            .map(|fixed_schedule| fixed_schedule.label.clone())
+            .collect()
+    };
+    edit_fixed_schedule(app, &CoreSchedule::FixedUpdate, &*setup);
+    for label in labels {
+        edit_fixed_schedule(app, &*label, &*setup);
+    }
+}
+
+/// Similar to [`App::edit_schedule`], but works with boxed labels.
+fn edit_fixed_schedule(app: &mut App, label: &dyn ScheduleLabel, f: &dyn Fn(&mut Schedule)) {
+    let mut schedules = app.world.resource_mut::<Schedules>();
+    if schedules.get(label).is_none() {
+        schedules.insert(label.dyn_clone(), Schedule::new());
+    }
+    if let Some(schedule) = schedules.get_mut(label) {
+        f(schedule);
+    }
+}
+
+/// Give every schedule added with [`AddFixedSchedule`] its own copy of `R`, swapped in while the
+/// schedule runs. Copies are cloned from the current value of `R` when a schedule is added.
+pub(crate) fn add_fixed_schedule_local<R: Resource + Clone>(app: &mut App) {
+    add_fixed_schedule_local_with::<R>(app, push_fixed_schedule_local::<R>);
+}
+
+/// Similar to [`add_fixed_schedule_local`], but copies start out as [`Default::default`].
+pub(crate) fn add_fixed_schedule_local_default<R: Resource + Default>(app: &mut App) {
+    add_fixed_schedule_local_with::<R>(app, push_fixed_schedule_local_default::<R>);
+}
+
+fn add_fixed_schedule_local_with<R: Resource>(app: &mut App, push: fn(&mut App)) {
+    if app.world.contains_resource::<FixedScheduleLocal<R>>() {
+        return;
+    }
+    app.insert_resource(FixedScheduleLocal::<R>(vec![]));
+    let count = {
+        let mut fixed_schedules = app
+            .world
+            .get_resource_or_insert_with(FixedSchedules::default);
+        fixed_schedules.locals.push(push);
+        fixed_schedules.swaps.push(swap_fixed_schedule_local::<R>);
+        fixed_schedules.schedules.len()
+    };
+    for _ in 0..count {
+        push(app);
+    }
+}
+
+/// The copies of `R` belonging to each schedule added with [`AddFixedSchedule`]. Copies are
+/// swapped with the real resource while their schedule runs.
+#[derive(Resource)]
+pub(crate) struct FixedScheduleLocal<R: Resource>(Vec<R>);
+
+impl<R: Resource> FixedScheduleLocal<R> {
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut R> {
+        self.0.iter_mut()
+    }
+}
+
+fn push_fixed_schedule_local<R: Resource + Clone>(app: &mut App) {
+    let resource = app.world.resource::<R>().clone();
+    app.world
+        .resource_mut::<FixedScheduleLocal<R>>()
+        .0
+        .push(resource);
+}
+
+fn push_fixed_schedule_local_default<R: Resource + Default>(app: &mut App) {
+    app.world
+        .resource_mut::<FixedScheduleLocal<R>>()
+        .0
+        .push(R::default());
+}
+
+fn swap_fixed_schedule_local<R: Resource>(world: &mut World, index: usize) {
+    world.resource_scope(|world, mut local: Mut<FixedScheduleLocal<R>>| {
+        if let Some(mut resource) = world.get_resource_mut::<R>() {
+            mem::swap(resource.as_mut(), &mut local.0[index - 1]);
+        }
+    });
+}
+
+fn fixed_schedules_run(world: &mut World) {
+    let control = world
+        .get_resource::<FixedTimeControl>()
+        .cloned()
+        .unwrap_or_default();
+    let delta = match world.get_resource::<Time>() {
+        Some(time) if !control.is_paused() => time.delta().mul_f64(f64::from(control.scale())),
+        _ => Duration::ZERO,
+    };
+    let max_ticks = control.max_ticks_per_frame().unwrap_or(u32::MAX);
+    let count = world.resource::<FixedSchedules>().schedules.len();
+    for index in 0..count {
+        let mut dropped = {
+            let mut fixed_schedules = world.resource_mut::<FixedSchedules>();
+            let fixed_time = &mut fixed_schedules.schedules[index].fixed_time;
+            let (delta, dropped) = control.limit_delta(delta, fixed_time);
+            fixed_time.tick(delta);
+            dropped
+        };
+        let mut ticks = 0;
+        while ticks < max_ticks {
+            let mut fixed_schedules = world.resource_mut::<FixedSchedules>();
+            if fixed_schedules.schedules[index]
+                .fixed_time
+                .expend()
+                .is_err()
+            {
+                break;
+            }
+            let label = fixed_schedules.schedules[index].label.clone();
+            let swaps = fixed_schedules.swaps.clone();
+            for swap in &swaps {
+                swap(world, index + 1);
+            }
+            world.run_schedule_ref(&*label);
+            for swap in &swaps {
+                swap(world, index + 1);
+            }
+            ticks += 1;
+        }
+        let mut fixed_schedules = world.resource_mut::<FixedSchedules>();
+        let fixed_schedule = &mut fixed_schedules.schedules[index];
+        if control.catch_up() == CatchUp::Drop {
+            dropped += drop_fixed_ticks(&mut fixed_schedule.fixed_time);
+        }
+        if dropped > 0 {
+            let schedule = fixed_schedule.label.clone();
+            world.send_event(FixedTimestepOverrun {
+                schedule,
+                ticks,
+                dropped,
+            });
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use std::hash::Hash;
+
+use bevy::prelude::*;
+
+use super::{AddFixedEvent, FixedInput, FixedSet};
+
+/// System set for recognizing [`InputSequence`]s.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub struct InputSequenceSystem;
+
+/// A trait implemented by [`App`] allowing recognition of input sequences and chords.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::prelude::*;
+/// #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+/// pub enum Move {
+///     Fireball,
+///     Save,
+/// }
+///
+/// App::new()
+///     .add_input_sequences::<KeyCode, Move>()
+///     .insert_resource(
+///         InputSequences::new()
+///             .with_sequence(
+///                 Move::Fireball,
+///                 InputSequence::new()
+///                     .then(SequenceStep::new([KeyCode::S]).without([KeyCode::D]))
+///                     .then([KeyCode::S, KeyCode::D])
+///                     .then(SequenceStep::new([KeyCode::D]).without([KeyCode::S]))
+///                     .then(KeyCode::J)
+///                     .with_priority(1),
+///             )
+///             .with_sequence(
+///                 Move::Save,
+///                 InputSequence::chord([KeyCode::LControl, KeyCode::LShift, KeyCode::S]),
+///             ),
+///     );
+/// ```
+pub trait AddInputSequences {
+    /// Recognize sequences of `T` from [`FixedInput<T>`], identified by `S`. Recognized sequences
+    /// are sent as [`InputSequenceEvent<S>`] during [`FixedSet::PreUpdate`].
+    ///
+    /// [`FixedInput<T>`] must be registered (see [`AddFixedInput`](`super::AddFixedInput`)).
+    fn add_input_sequences<
+        T: Copy + Eq + Hash + Send + Sync + 'static,
+        S: Copy + Eq + Hash + Send + Sync + 'static,
+    >(
+        &mut self,
+    ) -> &mut Self;
+}
+
+impl AddInputSequences for App {
+    fn add_input_sequences<
+        T: Copy + Eq + Hash + Send + Sync + 'static,
+        S: Copy + Eq + Hash + Send + Sync + 'static,
+    >(
+        &mut self,
+    ) -> &mut Self {
+        self.init_resource::<InputSequences<T, S>>()
+            .add_fixed_event::<InputSequenceEvent<S>>()
+            .add_system(
+                input_sequences_update::<T, S>
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(InputSequenceSystem)
+                    .in_base_set(FixedSet::PreUpdate),
+            );
+        self
+    }
+}
+
+/// Sent when an [`InputSequence`] is recognized.
+#[derive(Clone, Copy, Debug)]
+pub struct InputSequenceEvent<S: Copy + Eq + Hash + Send + Sync + 'static> {
+    /// The identifier of the recognized sequence.
+    pub sequence: S,
+}
+
+/// A single step in an [`InputSequence`].
+///
+/// A step matches on the tick it becomes satisfied: all of its inputs are pressed, none of its
+/// excluded inputs are pressed, and one of those conditions only just became true.
+#[derive(Clone, Debug)]
+pub struct SequenceStep<T: Copy + Eq + Hash + Send + Sync + 'static> {
+    pressed: Vec<T>,
+    excluded: Vec<T>,
+    within: u32,
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static> SequenceStep<T> {
+    /// The default number of ticks allowed between a step and the previous step.
+    pub const DEFAULT_WITHIN: u32 = 10;
+
+    /// Create a step which requires all `inputs` to be pressed at the same time.
+    #[must_use]
+    pub fn new(inputs: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            pressed: inputs.into_iter().collect(),
+            excluded: vec![],
+            within: Self::DEFAULT_WITHIN,
+        }
+    }
+
+    /// Returns this step, additionally requiring `inputs` to not be pressed. Useful for exclusive
+    /// directions, such as "forward" not matching "down-forward".
+    #[must_use]
+    pub fn without(mut self, inputs: impl IntoIterator<Item = T>) -> Self {
+        self.excluded.extend(inputs);
+        self
+    }
+
+    /// Returns this step, requiring that it matches within `ticks` ticks of the previous step.
+    /// Has no effect on the first step of a sequence.
+    #[must_use]
+    pub fn within(mut self, ticks: u32) -> Self {
+        self.within = ticks;
+        self
+    }
+
+    fn just_matched(&self, fixed_input: &FixedInput<T>) -> bool {
+        if self.pressed.is_empty() {
+            return false;
+        }
+        let satisfied = self.pressed.iter().all(|input| fixed_input.pressed(*input))
+            && !fixed_input.any_pressed(self.excluded.iter().copied());
+        satisfied
+            && (fixed_input.any_just_pressed(self.pressed.iter().copied())
+                || fixed_input.any_just_released(self.excluded.iter().copied()))
+    }
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static> From<T> for SequenceStep<T> {
+    fn from(input: T) -> Self {
+        Self::new([input])
+    }
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static, const N: usize> From<[T; N]> for SequenceStep<T> {
+    fn from(inputs: [T; N]) -> Self {
+        Self::new(inputs)
+    }
+}
+
+/// A declarative sequence of [`SequenceStep`]s, such as a fighting game motion input or an editor
+/// chord.
+#[derive(Clone, Debug)]
+pub struct InputSequence<T: Copy + Eq + Hash + Send + Sync + 'static> {
+    steps: Vec<SequenceStep<T>>,
+    priority: i32,
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static> Default for InputSequence<T> {
+    fn default() -> Self {
+        Self {
+            steps: vec![],
+            priority: 0,
+        }
+    }
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static> InputSequence<T> {
+    /// Create an empty [`InputSequence`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an [`InputSequence`] with a single step requiring all `inputs` to be pressed at the
+    /// same time.
+    #[must_use]
+    pub fn chord(inputs: impl IntoIterator<Item = T>) -> Self {
+        Self::new().then(SequenceStep::new(inputs))
+    }
+
+    /// Returns this sequence with an additional step.
+    #[must_use]
+    pub fn then(mut self, step: impl Into<SequenceStep<T>>) -> Self {
+        self.steps.push(step.into());
+        self
+    }
+
+    /// Returns this sequence with a new priority.
+    ///
+    /// When multiple sequences are recognized on the same tick, only those with the highest
+    /// priority are sent. Ties are broken by the number of steps, preferring longer sequences.
+    #[must_use]
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// The steps in this sequence.
+    #[must_use]
+    pub fn steps(&self) -> &[SequenceStep<T>] {
+        &self.steps
+    }
+
+    /// The priority of this sequence.
+    #[must_use]
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+}
+
+#[derive(Clone, Debug)]
+struct SequenceEntry<
+    T: Copy + Eq + Hash + Send + Sync + 'static,
+    S: Copy + Eq + Hash + Send + Sync + 'static,
+> {
+    id: S,
+    sequence: InputSequence<T>,
+    progress: usize,
+    last_step_tick: u64,
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static, S: Copy + Eq + Hash + Send + Sync + 'static>
+    SequenceEntry<T, S>
+{
+    fn advance(&mut self, fixed_input: &FixedInput<T>) -> bool {
+        let steps = &self.sequence.steps;
+        if steps.is_empty() {
+            return false;
+        }
+        let tick = fixed_input.current_tick();
+        if self.progress > 0
+            && tick.saturating_sub(self.last_step_tick) > u64::from(steps[self.progress].within)
+        {
+            self.progress = 0;
+        }
+        if steps[self.progress].just_matched(fixed_input) {
+            self.progress += 1;
+            self.last_step_tick = tick;
+        } else if self.progress > 0 && steps[0].just_matched(fixed_input) {
+            self.progress = 1;
+            self.last_step_tick = tick;
+        }
+        if self.progress == steps.len() {
+            self.progress = 0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Input sequences of `T` to recognize, identified by `S`.
+///
+/// See [`AddInputSequences`].
+#[derive(Clone, Debug, Resource)]
+pub struct InputSequences<
+    T: Copy + Eq + Hash + Send + Sync + 'static,
+    S: Copy + Eq + Hash + Send + Sync + 'static,
+> {
+    entries: Vec<SequenceEntry<T, S>>,
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static, S: Copy + Eq + Hash + Send + Sync + 'static>
+    Default for InputSequences<T, S>
+{
+    fn default() -> Self {
+        Self { entries: vec![] }
+    }
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static, S: Copy + Eq + Hash + Send + Sync + 'static>
+    InputSequences<T, S>
+{
+    /// Create an empty [`InputSequences`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns this [`InputSequences`] with an additional sequence.
+    #[must_use]
+    pub fn with_sequence(mut self, id: S, sequence: InputSequence<T>) -> Self {
+        self.add(id, sequence);
+        self
+    }
+
+    /// Add a sequence, replacing any existing sequence with the same `id`.
+    pub fn add(&mut self, id: S, sequence: InputSequence<T>) {
+        self.remove(id);
+        self.entries.push(SequenceEntry {
+            id,
+            sequence,
+            progress: 0,
+            last_step_tick: 0,
+        });
+    }
+
+    /// Remove a sequence. Returns `false` if the sequence did not exist.
+    pub fn remove(&mut self, id: S) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|entry| entry.id != id);
+        self.entries.len() != len
+    }
+
+    /// Get a sequence.
+    #[must_use]
+    pub fn get(&self, id: S) -> Option<&InputSequence<T>> {
+        self.entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| &entry.sequence)
+    }
+
+    /// Forget any partially recognized sequences.
+    pub fn reset(&mut self) {
+        for entry in &mut self.entries {
+            entry.progress = 0;
+        }
+    }
+}
+
+fn input_sequences_update<
+    T: Copy + Eq + Hash + Send + Sync + 'static,
+    S: Copy + Eq + Hash + Send + Sync + 'static,
+>(
+    mut input_sequences: ResMut<InputSequences<T, S>>,
+    mut input_sequence_events: EventWriter<InputSequenceEvent<S>>,
+    fixed_input: Res<FixedInput<T>>,
+) {
+    let recognized: Vec<(S, i32, usize)> = input_sequences
+        .entries
+        .iter_mut()
+        .filter_map(|entry| {
+            entry.advance(fixed_input.as_ref()).then_some((
+                entry.id,
+                entry.sequence.priority,
+                entry.sequence.steps.len(),
+            ))
+        })
+        .collect();
+    let Some(best) = recognized
+        .iter()
+        .map(|(_, priority, steps)| (*priority, *steps))
+        .max()
+    else {
+        return;
+    };
+    for (sequence, priority, steps) in recognized {
+        if (priority, steps) == best {
+            input_sequence_events.send(InputSequenceEvent { sequence });
+        }
+    }
+}
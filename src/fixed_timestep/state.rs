@@ -0,0 +1,81 @@
+use bevy::{
+    ecs::schedule::{apply_state_transition, run_enter_schedule},
+    prelude::*,
+};
+
+use super::FixedSet;
+
+/// A trait implemented by [`App`], similar to [`App::add_state`], which applies state transitions
+/// between fixed ticks.
+///
+/// [`NextState`] is applied during [`FixedSet::StateTransitions`] of the next tick, so every
+/// system from [`FixedSet::Update`] onwards sees the same state. [`OnExit`] and [`OnEnter`]
+/// schedules run after [`FixedSet::First`] and [`FixedSet::PreUpdate`], which still see the
+/// previous state. [`OnUpdate`] sets run in [`FixedSet::Update`] of the fixed schedule.
+///
+/// Works with [`AddStateCleanup`](`crate::cleanup::AddStateCleanup`), which may be called before
+/// or after this.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::prelude::*;
+/// #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, States)]
+/// pub enum GameState {
+///     #[default]
+///     Playing,
+///     GameOver,
+/// }
+///
+/// fn lose(mut next_state: ResMut<NextState<GameState>>) {
+///     next_state.set(GameState::GameOver);
+/// }
+///
+/// App::new()
+///     .add_plugins(HaliaPlugins)
+///     .add_fixed_state::<GameState>()
+///     .add_system(
+///         lose.in_set(OnUpdate(GameState::Playing))
+///             .in_schedule(CoreSchedule::FixedUpdate),
+///     );
+/// ```
+pub trait AddFixedState {
+    /// Setup the application to manage a state of type S (similar to [`App::add_state`]), but
+    /// with transitions applied between fixed ticks. `S` must not also be added with
+    /// [`App::add_state`].
+    fn add_fixed_state<S: States>(&mut self) -> &mut Self;
+}
+
+impl AddFixedState for App {
+    fn add_fixed_state<S: States>(&mut self) -> &mut Self {
+        self.init_resource::<State<S>>()
+            .init_resource::<NextState<S>>()
+            .add_systems(
+                (
+                    run_enter_schedule::<S>.run_if(run_once()),
+                    apply_state_transition::<S>,
+                )
+                    .chain()
+                    .in_base_set(FixedSet::StateTransitions)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+                for variant in S::variants() {
+                    schedule.configure_set(
+                        OnUpdate(variant.clone())
+                            .in_base_set(FixedSet::Update)
+                            .run_if(in_state(variant)),
+                    );
+                }
+            });
+        // unlike App::add_state, keep schedules which already have systems
+        for variant in S::variants() {
+            if self.get_schedule(OnEnter(variant.clone())).is_none() {
+                self.add_schedule(OnEnter(variant.clone()), Schedule::new());
+            }
+            if self.get_schedule(OnExit(variant.clone())).is_none() {
+                self.add_schedule(OnExit(variant), Schedule::new());
+            }
+        }
+        self
+    }
+}
@@ -0,0 +1,173 @@
+use bevy::{input::InputSystem, prelude::*, window::Ime};
+
+use super::{
+    add_fixed_schedule_local, for_each_fixed_schedule, FixedInputSystem, FixedScheduleLocal,
+    FixedSet,
+};
+
+/// A trait implemented by [`App`] allowing text to be typed into fixed timestep systems.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::prelude::*;
+/// #[derive(Default, Resource)]
+/// struct PlayerName(String);
+///
+/// fn edit_name(text_input: Res<FixedTextInput>, mut player_name: ResMut<PlayerName>) {
+///     text_input.apply(&mut player_name.0);
+/// }
+///
+/// App::new()
+///     .add_plugins(HaliaPlugins)
+///     .add_fixed_text_input()
+///     .init_resource::<PlayerName>()
+///     .add_system(edit_name.in_schedule(CoreSchedule::FixedUpdate));
+/// ```
+pub trait AddFixedTextInput {
+    /// Add [`FixedTextInput`], which latches [`ReceivedCharacter`] and [`Ime`] events for fixed
+    /// timestep systems.
+    fn add_fixed_text_input(&mut self) -> &mut Self;
+}
+
+impl AddFixedTextInput for App {
+    fn add_fixed_text_input(&mut self) -> &mut Self {
+        self.add_event::<ReceivedCharacter>()
+            .add_event::<Ime>()
+            .init_resource::<FixedTextInput>()
+            .add_system(
+                fixed_text_input_update
+                    .in_set(FixedInputSystem)
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(InputSystem),
+            );
+        for_each_fixed_schedule(self, add_fixed_text_input_clear);
+        add_fixed_schedule_local::<FixedTextInput>(self);
+        self
+    }
+}
+
+fn add_fixed_text_input_clear(schedule: &mut Schedule) {
+    schedule.add_system(
+        fixed_text_input_clear
+            .in_set(FixedInputSystem)
+            .in_base_set(FixedSet::Last),
+    );
+}
+
+/// An edit latched into [`FixedTextInput`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TextEdit {
+    /// A character was typed.
+    Char(char),
+    /// Backspace was pressed.
+    Backspace,
+    /// Text was committed by an input method editor.
+    Commit(String),
+}
+
+/// A fixed timestep buffer of typed text (see [`AddFixedTextInput`]).
+///
+/// Characters, backspaces and IME commits are accumulated between ticks, seen by every fixed
+/// timestep system during the next tick, and cleared at the end of that tick. Each edit is seen by
+/// exactly one tick, however many ticks run each frame.
+#[derive(Clone, Debug, Default, Resource)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct FixedTextInput {
+    edits: Vec<TextEdit>,
+    preedit: String,
+}
+
+impl FixedTextInput {
+    /// The edits latched since the last tick, in the order they happened.
+    #[must_use]
+    pub fn edits(&self) -> &[TextEdit] {
+        &self.edits
+    }
+
+    /// Returns `true` if nothing was typed since the last tick.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// The text typed since the last tick, ignoring backspaces.
+    #[must_use]
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for edit in &self.edits {
+            match edit {
+                TextEdit::Char(char) => text.push(*char),
+                TextEdit::Commit(commit) => text.push_str(commit),
+                TextEdit::Backspace => {}
+            }
+        }
+        text
+    }
+
+    /// Apply the edits latched since the last tick to `text`.
+    pub fn apply(&self, text: &mut String) {
+        for edit in &self.edits {
+            match edit {
+                TextEdit::Char(char) => text.push(*char),
+                TextEdit::Commit(commit) => text.push_str(commit),
+                TextEdit::Backspace => {
+                    text.pop();
+                }
+            }
+        }
+    }
+
+    /// Text being composed by an input method editor, which has not been committed yet. Updated
+    /// every frame, so it can be displayed before it reaches a tick.
+    #[must_use]
+    pub fn preedit(&self) -> &str {
+        &self.preedit
+    }
+
+    /// Apply an edit as if it came from [`ReceivedCharacter`] or [`Ime`].
+    pub fn latch(&mut self, edit: TextEdit) {
+        self.edits.push(edit);
+    }
+}
+
+fn fixed_text_input_update(
+    mut fixed_text_input: ResMut<FixedTextInput>,
+    mut locals: ResMut<FixedScheduleLocal<FixedTextInput>>,
+    mut received_characters: EventReader<ReceivedCharacter>,
+    mut ime_events: EventReader<Ime>,
+) {
+    let mut edits = vec![];
+    let mut preedit = None;
+    for received_character in &mut received_characters {
+        match received_character.char {
+            '\u{8}' | '\u{7f}' => edits.push(TextEdit::Backspace),
+            char if char.is_control() => {}
+            char => edits.push(TextEdit::Char(char)),
+        }
+    }
+    for ime in &mut ime_events {
+        match ime {
+            Ime::Preedit { value, .. } => preedit = Some(value.clone()),
+            Ime::Commit { value, .. } => {
+                edits.push(TextEdit::Commit(value.clone()));
+                preedit = Some(String::new());
+            }
+            Ime::Disabled { .. } => preedit = Some(String::new()),
+            Ime::Enabled { .. } => {}
+        }
+    }
+    if edits.is_empty() && preedit.is_none() {
+        return;
+    }
+    for fixed_text_input in std::iter::once(fixed_text_input.as_mut()).chain(locals.iter_mut()) {
+        fixed_text_input.edits.extend(edits.iter().cloned());
+        if let Some(preedit) = &preedit {
+            fixed_text_input.preedit.clone_from(preedit);
+        }
+    }
+}
+
+fn fixed_text_input_clear(mut fixed_text_input: ResMut<FixedTextInput>) {
+    fixed_text_input.edits.clear();
+}
@@ -0,0 +1,310 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::{add_fixed_schedule_local, for_each_fixed_schedule, FixedSet};
+
+/// System set for incrementing [`FixedTick`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub struct FixedTickSystem;
+
+pub(crate) struct FixedTimestepTimePlugin;
+
+impl Plugin for FixedTimestepTimePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FixedTick>();
+        for_each_fixed_schedule(app, add_fixed_tick_update);
+        add_fixed_schedule_local::<FixedTick>(app);
+    }
+}
+
+fn add_fixed_tick_update(schedule: &mut Schedule) {
+    schedule.add_system(
+        fixed_tick_update
+            .in_set(FixedTickSystem)
+            .in_base_set(FixedSet::PreUpdate),
+    );
+}
+
+/// The number of fixed ticks which have run, including the current tick.
+///
+/// Incremented at the start of [`FixedSet::PreUpdate`], so the first tick is tick 1. Systems in
+/// [`FixedSet::First`] still see the previous tick. Each schedule added with
+/// [`AddFixedSchedule`](`super::AddFixedSchedule`) counts its own ticks.
+#[derive(
+    Clone, Copy, Debug, Default, Deref, DerefMut, Eq, Hash, Ord, PartialEq, PartialOrd, Resource,
+)]
+pub struct FixedTick(pub u64);
+
+fn fixed_tick_update(mut fixed_tick: ResMut<FixedTick>) {
+    fixed_tick.0 += 1;
+}
+
+/// A length of time measured in fixed ticks.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FixedDuration {
+    /// An exact number of ticks.
+    Ticks(u64),
+    /// A duration, rounded up to a whole number of ticks using [`FixedTime::period`].
+    Time(Duration),
+}
+
+impl FixedDuration {
+    /// The number of ticks in this duration, given the [`FixedTime::period`].
+    #[must_use]
+    pub fn ticks(self, period: Duration) -> u64 {
+        match self {
+            Self::Ticks(ticks) => ticks,
+            Self::Time(duration) => {
+                let period = period.as_nanos().max(1);
+                u64::try_from(duration.as_nanos().div_ceil(period)).unwrap_or(u64::MAX)
+            }
+        }
+    }
+}
+
+impl Default for FixedDuration {
+    fn default() -> Self {
+        Self::Ticks(0)
+    }
+}
+
+impl From<Duration> for FixedDuration {
+    fn from(duration: Duration) -> Self {
+        Self::Time(duration)
+    }
+}
+
+/// A fixed timestep version of [`Timer`], counting whole ticks so that it finishes on exactly the
+/// same tick every time.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::prelude::*;
+/// #[derive(Resource)]
+/// struct SpawnTimer(FixedTimer);
+///
+/// fn spawn(mut spawn_timer: ResMut<SpawnTimer>, fixed_time: Res<FixedTime>) {
+///     if spawn_timer.0.tick(fixed_time.period).just_finished() {
+///         // spawn something
+///     }
+/// }
+/// # bevy::ecs::system::assert_is_system(spawn);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FixedTimer {
+    duration: FixedDuration,
+    mode: TimerMode,
+    elapsed: u64,
+    paused: bool,
+    finished: bool,
+    times_finished_this_tick: u32,
+}
+
+impl FixedTimer {
+    /// Create a new timer lasting `duration`.
+    #[must_use]
+    pub fn new(duration: impl Into<FixedDuration>, mode: TimerMode) -> Self {
+        Self {
+            duration: duration.into(),
+            mode,
+            ..Default::default()
+        }
+    }
+
+    /// Create a new timer lasting an exact number of ticks.
+    #[must_use]
+    pub fn from_ticks(ticks: u64, mode: TimerMode) -> Self {
+        Self::new(FixedDuration::Ticks(ticks), mode)
+    }
+
+    /// Advance the timer by one tick. `period` should be [`FixedTime::period`], and is only used
+    /// if the duration is a [`FixedDuration::Time`].
+    pub fn tick(&mut self, period: Duration) -> &Self {
+        self.times_finished_this_tick = 0;
+        if self.paused || (self.mode == TimerMode::Once && self.finished) {
+            return self;
+        }
+        let length = self.duration.ticks(period);
+        self.elapsed += 1;
+        if self.elapsed >= length {
+            self.finished = true;
+            if self.mode == TimerMode::Repeating {
+                let length = length.max(1);
+                self.times_finished_this_tick =
+                    u32::try_from(self.elapsed / length).unwrap_or(u32::MAX);
+                self.elapsed %= length;
+            } else {
+                self.times_finished_this_tick = 1;
+                self.elapsed = length;
+            }
+        } else if self.mode == TimerMode::Repeating {
+            self.finished = false;
+        }
+        self
+    }
+
+    /// Returns `true` if the timer has reached its duration. For repeating timers, this is the
+    /// same as [`FixedTimer::just_finished`].
+    #[must_use]
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Returns `true` if the timer reached its duration during the last call to
+    /// [`FixedTimer::tick`].
+    #[must_use]
+    pub fn just_finished(&self) -> bool {
+        self.times_finished_this_tick > 0
+    }
+
+    /// The number of times the timer finished during the last call to [`FixedTimer::tick`]. Can
+    /// be more than 1 for repeating timers with a duration of 0 ticks, or if the duration was
+    /// shortened.
+    #[must_use]
+    pub fn times_finished_this_tick(&self) -> u32 {
+        self.times_finished_this_tick
+    }
+
+    /// The number of ticks elapsed since the timer started or last repeated.
+    #[must_use]
+    pub fn elapsed_ticks(&self) -> u64 {
+        self.elapsed
+    }
+
+    /// The time elapsed since the timer started or last repeated.
+    #[must_use]
+    pub fn elapsed(&self, period: Duration) -> Duration {
+        ticks_to_duration(self.elapsed, period)
+    }
+
+    /// The number of ticks remaining until the timer finishes.
+    #[must_use]
+    pub fn remaining_ticks(&self, period: Duration) -> u64 {
+        self.duration.ticks(period).saturating_sub(self.elapsed)
+    }
+
+    /// The fraction of the duration which has elapsed, from 0 to 1.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn percent(&self, period: Duration) -> f32 {
+        let length = self.duration.ticks(period);
+        if length == 0 {
+            1.
+        } else {
+            (self.elapsed as f64 / length as f64) as f32
+        }
+    }
+
+    /// The duration of the timer.
+    #[must_use]
+    pub fn duration(&self) -> FixedDuration {
+        self.duration
+    }
+
+    /// Set the duration of the timer.
+    pub fn set_duration(&mut self, duration: impl Into<FixedDuration>) {
+        self.duration = duration.into();
+    }
+
+    /// The [`TimerMode`] of the timer.
+    #[must_use]
+    pub fn mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    /// Set the [`TimerMode`] of the timer.
+    pub fn set_mode(&mut self, mode: TimerMode) {
+        self.mode = mode;
+    }
+
+    /// Pause the timer. Ticks have no effect while paused.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Unpause the timer.
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns `true` if the timer is paused.
+    #[must_use]
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Reset the timer to 0 elapsed ticks. Does not change whether it is paused.
+    pub fn reset(&mut self) {
+        self.elapsed = 0;
+        self.finished = false;
+        self.times_finished_this_tick = 0;
+    }
+}
+
+/// A fixed timestep version of [`Stopwatch`](`bevy::time::Stopwatch`), counting whole ticks.
+#[derive(Clone, Debug, Default)]
+pub struct FixedStopwatch {
+    elapsed: u64,
+    paused: bool,
+}
+
+impl FixedStopwatch {
+    /// Create a new stopwatch with 0 elapsed ticks.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advance the stopwatch by one tick.
+    pub fn tick(&mut self) -> &Self {
+        if !self.paused {
+            self.elapsed += 1;
+        }
+        self
+    }
+
+    /// The number of ticks elapsed.
+    #[must_use]
+    pub fn elapsed_ticks(&self) -> u64 {
+        self.elapsed
+    }
+
+    /// The time elapsed, given the [`FixedTime::period`].
+    #[must_use]
+    pub fn elapsed(&self, period: Duration) -> Duration {
+        ticks_to_duration(self.elapsed, period)
+    }
+
+    /// Set the number of ticks elapsed.
+    pub fn set_elapsed_ticks(&mut self, ticks: u64) {
+        self.elapsed = ticks;
+    }
+
+    /// Pause the stopwatch. Ticks have no effect while paused.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Unpause the stopwatch.
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns `true` if the stopwatch is paused.
+    #[must_use]
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Reset the stopwatch to 0 elapsed ticks. Does not change whether it is paused.
+    pub fn reset(&mut self) {
+        self.elapsed = 0;
+    }
+}
+
+fn ticks_to_duration(ticks: u64, period: Duration) -> Duration {
+    u32::try_from(ticks).map_or(Duration::MAX, |ticks| {
+        period.checked_mul(ticks).unwrap_or(Duration::MAX)
+    })
+}
@@ -1,13 +1,18 @@
-use bevy::{
-    prelude::*,
-    transform::systems::{propagate_transforms, sync_simple_transforms},
-};
+use std::iter;
+
+use bevy::{prelude::*, utils::HashSet};
 
-use crate::transform2::transform2_propagate;
+use crate::transform2::{apply_transform2, Depth, Transform2};
 
-use super::FixedSet;
+use super::{
+    add_fixed_schedule_local_default, for_each_fixed_schedule, FixedScheduleLocal, FixedSet,
+};
 
 /// Fixed timestep transform propagation system set.
+///
+/// Unlike the frame rate versions, these systems only visit entities whose transforms or
+/// hierarchy changed since the last tick, along with their descendants, so static entities cost
+/// nothing.
 #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
 pub enum FixedTransformSystem {
     /// Fixed timestep version of
@@ -18,24 +23,207 @@ pub enum FixedTransformSystem {
     TransformPropagate,
 }
 
+/// A marker component which excludes an entity and its descendants from fixed timestep transform
+/// propagation.
+///
+/// Useful for entities such as decorations and UI which never need an up to date
+/// [`GlobalTransform`] during a tick. They are still propagated every frame as usual.
+#[derive(Clone, Component, Copy, Debug, Default)]
+pub struct NoFixedPropagation;
+
 pub(crate) struct FixedTimestepPropagatePlugin;
 
 impl Plugin for FixedTimestepPropagatePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems((
-            transform2_propagate
-                .in_schedule(CoreSchedule::FixedUpdate)
-                .in_set(FixedTransformSystem::Transform2Propagate)
-                .in_base_set(FixedSet::PostUpdate)
-                .before(FixedTransformSystem::TransformPropagate),
-            sync_simple_transforms
-                .in_schedule(CoreSchedule::FixedUpdate)
-                .in_set(FixedTransformSystem::TransformPropagate)
-                .in_base_set(FixedSet::PostUpdate),
-            propagate_transforms
-                .in_schedule(CoreSchedule::FixedUpdate)
-                .in_set(FixedTransformSystem::TransformPropagate)
-                .in_base_set(FixedSet::PostUpdate),
-        ));
+        app.init_resource::<RemovedParents>()
+            .add_system(removed_parents_update.in_base_set(CoreSet::Last));
+        add_fixed_schedule_local_default::<RemovedParents>(app);
+        for_each_fixed_schedule(app, add_fixed_propagation);
+    }
+}
+
+/// Entities whose [`Parent`] was removed since the last tick.
+///
+/// [`RemovedComponents`] only keeps removals for a couple of frames, so they are collected every
+/// frame and kept until a tick propagates them.
+#[derive(Default, Resource)]
+struct RemovedParents(HashSet<Entity>);
+
+fn removed_parents_update(
+    mut removed_parents: ResMut<RemovedParents>,
+    mut locals: ResMut<FixedScheduleLocal<RemovedParents>>,
+    mut removed_parent_components: RemovedComponents<Parent>,
+) {
+    let removed: Vec<Entity> = removed_parent_components.iter().collect();
+    for removed_parents in iter::once(removed_parents.as_mut()).chain(locals.iter_mut()) {
+        removed_parents.0.extend(removed.iter().copied());
+    }
+}
+
+fn removed_parents_clear(mut removed_parents: ResMut<RemovedParents>) {
+    removed_parents.0.clear();
+}
+
+fn add_fixed_propagation(schedule: &mut Schedule) {
+    schedule.add_systems((
+        fixed_transform2_propagate
+            .in_set(FixedTransformSystem::Transform2Propagate)
+            .in_base_set(FixedSet::PostUpdate)
+            .before(FixedTransformSystem::TransformPropagate),
+        fixed_transform_propagate
+            .in_set(FixedTransformSystem::TransformPropagate)
+            .in_base_set(FixedSet::PostUpdate),
+        removed_parents_clear
+            .in_base_set(FixedSet::PostUpdate)
+            .after(FixedTransformSystem::TransformPropagate),
+    ));
+}
+
+fn ancestors(entity: Entity, parent_query: &Query<&Parent>) -> Vec<Entity> {
+    iter::successors(parent_query.get(entity).ok(), |parent| {
+        parent_query.get(parent.get()).ok()
+    })
+    .map(|parent| parent.get())
+    .collect()
+}
+
+/// The entities in `changed` which are not opted out and have no changed ancestor, since
+/// updating an entity also updates its descendants.
+fn changed_roots(
+    changed: &HashSet<Entity>,
+    parent_query: &Query<&Parent>,
+    opt_out_query: &Query<(), With<NoFixedPropagation>>,
+) -> Vec<Entity> {
+    changed
+        .iter()
+        .copied()
+        .filter(|entity| !opt_out_query.contains(*entity))
+        .filter(|entity| {
+            !ancestors(*entity, parent_query)
+                .into_iter()
+                .any(|ancestor| changed.contains(&ancestor) || opt_out_query.contains(ancestor))
+        })
+        .collect()
+}
+
+fn fixed_transform2_propagate(
+    changed_query: Query<
+        Entity,
+        (
+            With<Transform>,
+            Or<(Changed<Transform2>, Changed<Depth>, Changed<Parent>)>,
+        ),
+    >,
+    mut transform_query: Query<
+        (&mut Transform, Option<&Transform2>, Option<&Depth>),
+        Without<NoFixedPropagation>,
+    >,
+    mut removed_parent_components: RemovedComponents<Parent>,
+    removed_parents: Res<RemovedParents>,
+    parent_query: Query<&Parent>,
+    children_query: Query<&Children>,
+    opt_out_query: Query<(), With<NoFixedPropagation>>,
+) {
+    let mut changed: HashSet<Entity> = changed_query.iter().collect();
+    changed.extend(
+        removed_parent_components
+            .iter()
+            .chain(removed_parents.0.iter().copied())
+            .filter(|entity| transform_query.contains(*entity)),
+    );
+    for entity in changed_roots(&changed, &parent_query, &opt_out_query) {
+        let cumulative_depth = ancestors(entity, &parent_query)
+            .into_iter()
+            .filter_map(|ancestor| transform_query.get(ancestor).ok())
+            .map(|(transform, ..)| transform.translation.z)
+            .sum();
+        update_transform2_recursive(
+            entity,
+            &children_query,
+            &mut transform_query,
+            cumulative_depth,
+        );
+    }
+}
+
+fn update_transform2_recursive(
+    entity: Entity,
+    children_query: &Query<&Children>,
+    transform_query: &mut Query<
+        (&mut Transform, Option<&Transform2>, Option<&Depth>),
+        Without<NoFixedPropagation>,
+    >,
+    mut cumulative_depth: f32,
+) {
+    let Ok((mut transform, transform2, depth)) = transform_query.get_mut(entity) else {
+        return;
+    };
+    cumulative_depth = apply_transform2(&mut transform, transform2, depth, cumulative_depth);
+    if let Ok(children) = children_query.get(entity) {
+        for child in children {
+            update_transform2_recursive(*child, children_query, transform_query, cumulative_depth);
+        }
+    }
+}
+
+fn fixed_transform_propagate(
+    changed_query: Query<
+        Entity,
+        (
+            With<GlobalTransform>,
+            Or<(Changed<Transform>, Changed<Parent>, Changed<Children>)>,
+        ),
+    >,
+    mut removed_parent_components: RemovedComponents<Parent>,
+    removed_parents: Res<RemovedParents>,
+    mut transform_query: Query<(&Transform, &mut GlobalTransform), Without<NoFixedPropagation>>,
+    parent_query: Query<&Parent>,
+    children_query: Query<&Children>,
+    opt_out_query: Query<(), With<NoFixedPropagation>>,
+) {
+    let mut changed: HashSet<Entity> = changed_query.iter().collect();
+    changed.extend(
+        removed_parent_components
+            .iter()
+            .chain(removed_parents.0.iter().copied())
+            .filter(|entity| transform_query.contains(*entity)),
+    );
+    for entity in changed_roots(&changed, &parent_query, &opt_out_query) {
+        let parent_global_transform = match parent_query.get(entity) {
+            Ok(parent) => match transform_query.get(parent.get()) {
+                Ok((_, global_transform)) => *global_transform,
+                Err(_) => continue,
+            },
+            Err(_) => GlobalTransform::IDENTITY,
+        };
+        propagate_recursive(
+            entity,
+            &children_query,
+            &mut transform_query,
+            parent_global_transform,
+        );
+    }
+}
+
+fn propagate_recursive(
+    entity: Entity,
+    children_query: &Query<&Children>,
+    transform_query: &mut Query<(&Transform, &mut GlobalTransform), Without<NoFixedPropagation>>,
+    parent_global_transform: GlobalTransform,
+) {
+    let Ok((transform, mut global_transform)) = transform_query.get_mut(entity) else {
+        return;
+    };
+    let new_global_transform = parent_global_transform.mul_transform(*transform);
+    global_transform.set_if_neq(new_global_transform);
+    if let Ok(children) = children_query.get(entity) {
+        for child in children {
+            propagate_recursive(
+                *child,
+                children_query,
+                transform_query,
+                new_global_transform,
+            );
+        }
     }
 }
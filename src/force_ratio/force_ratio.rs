@@ -1,4 +1,11 @@
-use bevy::{prelude::*, transform::TransformSystem};
+use std::ops::Range;
+
+use bevy::{
+    prelude::*,
+    render::view::{Layer, RenderLayers},
+    transform::TransformSystem,
+    utils::HashMap,
+};
 
 use crate::Persistent;
 
@@ -10,12 +17,16 @@ pub enum ForceRatioSystem {
     /// A [`CoreSchedule::Startup`] system that creates black bars ([`ForceRatioBar`]) on the edges
     /// of the screen.
     Setup,
-    /// A [`CoreSet::PostUpdate`] system that updates the camera's scale and adjusts
+    /// A [`CoreSet::PostUpdate`] system that creates black bars ([`ForceRatioBar`]) for cameras
+    /// with a [`ForceRatio`] component, and removes them when the component is removed or
+    /// disabled.
+    CameraSetup,
+    /// A [`CoreSet::PostUpdate`] system that updates the cameras' scales and adjusts
     /// [`ForceRatioBar`] entity positions.
     Update,
 }
 
-/// Adds force ratio functionality, configurable with the [`ForceRatio`] resource.
+/// Adds force ratio functionality, configurable with the [`ForceRatio`] resource and component.
 ///
 /// Contained within [`HaliaPlugins`](`crate::HaliaPlugins`).
 pub struct ForceRatioPlugin;
@@ -23,7 +34,14 @@ pub struct ForceRatioPlugin;
 impl Plugin for ForceRatioPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ForceRatio>()
+            .init_resource::<ForceRatioLayers>()
             .add_startup_system(force_ratio_setup.in_set(ForceRatioSystem::Setup))
+            .add_system(
+                force_ratio_camera_setup
+                    .in_set(ForceRatioSystem::CameraSetup)
+                    .in_base_set(CoreSet::PostUpdate)
+                    .before(ForceRatioSystem::Update),
+            )
             .add_system(
                 force_ratio_update
                     .in_set(ForceRatioSystem::Update)
@@ -36,21 +54,93 @@ impl Plugin for ForceRatioPlugin {
 /// A utility to force the screen to appear at a specified resolution.
 ///
 /// Forcing the screen ratio allows a game to be authored at only one resolution, either upscaling
-/// or downscaling if drawn at a different resolution. Black bars ([`ForceRatioBar`]) are placed
-/// around the screens to block out view if the screen ratio is not the same as the desired
-/// resolution.
-#[derive(Default, Resource, Copy, Clone, PartialEq)]
+/// or downscaling if drawn at a different resolution. Depending on the mode, black bars
+/// ([`ForceRatioBar`]) are placed around the screens to block out view if the screen ratio is not
+/// the same as the desired resolution.
+///
+/// As a resource, it applies to every camera. Inserting it as a component on a camera overrides
+/// the resource for that camera, with its own bars, which follow the camera and are only rendered
+/// by it (see [`ForceRatioLayers`]). Use [`ForceRatio::Disabled`] as a component to leave a
+/// camera, such as a minimap, unscaled.
+///
+/// The resource's bars are rendered by every camera which renders
+/// [`ForceRatioLayers::resource`], including cameras with a component, so the resource is usually
+/// disabled when cameras have their own.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::prelude::*;
+/// fn setup(mut force_ratio: ResMut<ForceRatio>, mut commands: Commands) {
+///     force_ratio.enable(1920., 1080.);
+///     commands.spawn(Camera2dBundle::default());
+///     commands.spawn((
+///         Camera2dBundle {
+///             camera: Camera {
+///                 order: 1,
+///                 ..Default::default()
+///             },
+///             ..Default::default()
+///         },
+///         ForceRatio::PixelPerfect {
+///             width: 160.,
+///             height: 90.,
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Default, Component, Resource, Copy, Clone, PartialEq)]
 pub enum ForceRatio {
     /// Force ratio is disabled.
     #[default]
     Disabled,
-    /// Force ratio is enabled with the specified resolution.
+    /// Force ratio is enabled with the specified resolution, which is scaled to fit the window.
+    /// Bars fill the remainder of the window.
     Enabled {
         /// The resolution's width
         width: f32,
         /// The resolution's height
         height: f32,
     },
+    /// The specified resolution is scaled by the largest whole multiple which fits the window's
+    /// physical pixels, so that pixels stay square and sharp. Bars fill the remainder of the
+    /// window.
+    ///
+    /// If the window is smaller than the resolution, it is drawn at one physical pixel per unit
+    /// and cropped.
+    PixelPerfect {
+        /// The resolution's width
+        width: f32,
+        /// The resolution's height
+        height: f32,
+    },
+    /// The specified height always fills the window, and more or less of the world is visible
+    /// horizontally depending on the window's ratio. No bars are shown.
+    ExpandWidth {
+        /// The resolution's height
+        height: f32,
+    },
+    /// The specified width always fills the window, and more or less of the world is visible
+    /// vertically depending on the window's ratio. No bars are shown.
+    ExpandHeight {
+        /// The resolution's width
+        width: f32,
+    },
+    /// The specified resolution is stretched to fill the window, ignoring the window's ratio. No
+    /// bars are shown.
+    Stretch {
+        /// The resolution's width
+        width: f32,
+        /// The resolution's height
+        height: f32,
+    },
+    /// The specified resolution is scaled to cover the window, cropping whichever axis overflows.
+    /// No bars are shown.
+    Fill {
+        /// The resolution's width
+        width: f32,
+        /// The resolution's height
+        height: f32,
+    },
 }
 
 impl ForceRatio {
@@ -66,12 +156,68 @@ impl ForceRatio {
     pub fn enable(&mut self, width: f32, height: f32) {
         *self = Self::Enabled { width, height };
     }
+
+    /// The camera scale for a window with the given physical size and scale factor (see
+    /// [`Window::physical_width`] and [`Window::scale_factor`]), or [`None`] if force ratio is
+    /// disabled.
+    ///
+    /// The scale is the size of the visible world divided by the logical size of the window.
+    /// [`ForceRatio::PixelPerfect`] picks its multiple from the physical size, so every unit of
+    /// the resolution covers a whole number of physical pixels.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use halia::prelude::*;
+    /// let force_ratio = ForceRatio::PixelPerfect { width: 320., height: 180. };
+    /// assert_eq!(force_ratio.scale(Vec2::new(1280., 720.), 1.), Some(Vec2::splat(0.25)));
+    /// assert_eq!(force_ratio.scale(Vec2::new(1440., 810.), 1.5), Some(Vec2::splat(0.375)));
+    /// ```
+    #[must_use]
+    pub fn scale(&self, physical_size: Vec2, scale_factor: f32) -> Option<Vec2> {
+        let window_size = physical_size / scale_factor;
+        match *self {
+            ForceRatio::Disabled => None,
+            ForceRatio::Enabled { width, height } => Some(Vec2::splat(
+                (width / window_size.x).max(height / window_size.y),
+            )),
+            ForceRatio::PixelPerfect { width, height } => {
+                let multiple = (physical_size.x / width)
+                    .min(physical_size.y / height)
+                    .floor()
+                    .max(1.);
+                Some(Vec2::splat(scale_factor / multiple))
+            }
+            ForceRatio::ExpandWidth { height } => Some(Vec2::splat(height / window_size.y)),
+            ForceRatio::ExpandHeight { width } => Some(Vec2::splat(width / window_size.x)),
+            ForceRatio::Stretch { width, height } => {
+                Some(Vec2::new(width / window_size.x, height / window_size.y))
+            }
+            ForceRatio::Fill { width, height } => Some(Vec2::splat(
+                (width / window_size.x).min(height / window_size.y),
+            )),
+        }
+    }
+
+    /// The size of the area surrounded by [`ForceRatioBar`]s, or [`None`] if no bars are shown.
+    fn bar_area(&self) -> Option<Vec2> {
+        match *self {
+            ForceRatio::Enabled { width, height } | ForceRatio::PixelPerfect { width, height } => {
+                Some(Vec2::new(width, height))
+            }
+            ForceRatio::Disabled
+            | ForceRatio::ExpandWidth { .. }
+            | ForceRatio::ExpandHeight { .. }
+            | ForceRatio::Stretch { .. }
+            | ForceRatio::Fill { .. } => None,
+        }
+    }
 }
 
 /// Black bar entities created at the edges of the screen to block out view when the screen's
 /// resolution is not the desired resolution.
 ///
-/// These entities are not visible if force ratio is disabled.
+/// These entities are not visible if force ratio is disabled, or its mode does not use bars.
+/// Bars created for a [`ForceRatio`] component also have a [`ForceRatioBarCamera`] component.
 #[derive(Component, Clone, Copy)]
 pub enum ForceRatioBar {
     /// The bar at the top of the screen.
@@ -86,82 +232,317 @@ pub enum ForceRatioBar {
 
 impl ForceRatioBar {
     fn visibility(force_ratio: &ForceRatio) -> Visibility {
-        if matches!(force_ratio, &ForceRatio::Disabled) {
-            Visibility::Hidden
-        } else {
+        if force_ratio.bar_area().is_some() {
             Visibility::Inherited
+        } else {
+            Visibility::Hidden
         }
     }
     fn translation(self, force_ratio: &ForceRatio) -> Vec3 {
-        match force_ratio {
-            ForceRatio::Disabled => Vec3::ZERO,
-            ForceRatio::Enabled { width, height } => match self {
-                ForceRatioBar::Top => Vec3::new(0., height * 0.5 + RATIO_BAR_SIZE * 0.5, 1.),
-                ForceRatioBar::Bottom => Vec3::new(0., height * -0.5 - RATIO_BAR_SIZE * 0.5, 1.),
-                ForceRatioBar::Left => Vec3::new(width * -0.5 - RATIO_BAR_SIZE * 0.5, 0., 1.),
-                ForceRatioBar::Right => Vec3::new(width * 0.5 + RATIO_BAR_SIZE * 0.5, 0., 1.),
-            },
+        let Some(Vec2 {
+            x: width,
+            y: height,
+        }) = force_ratio.bar_area()
+        else {
+            return Vec3::ZERO;
+        };
+        match self {
+            ForceRatioBar::Top => Vec3::new(0., height * 0.5 + RATIO_BAR_SIZE * 0.5, 1.),
+            ForceRatioBar::Bottom => Vec3::new(0., height * -0.5 - RATIO_BAR_SIZE * 0.5, 1.),
+            ForceRatioBar::Left => Vec3::new(width * -0.5 - RATIO_BAR_SIZE * 0.5, 0., 1.),
+            ForceRatioBar::Right => Vec3::new(width * 0.5 + RATIO_BAR_SIZE * 0.5, 0., 1.),
         }
     }
 }
 
-fn force_ratio_setup(mut commands: Commands, force_ratio: Res<ForceRatio>) {
-    for side in [
-        ForceRatioBar::Top,
-        ForceRatioBar::Bottom,
-        ForceRatioBar::Left,
-        ForceRatioBar::Right,
-    ] {
-        commands.spawn((
-            SpriteBundle {
-                sprite: Sprite {
-                    custom_size: Some(Vec2::splat(RATIO_BAR_SIZE)),
-                    color: Color::BLACK,
-                    ..Default::default()
-                },
-                visibility: ForceRatioBar::visibility(force_ratio.as_ref()),
-                transform: Transform::from_translation(side.translation(force_ratio.as_ref())),
+/// The render layers used by [`ForceRatioBar`]s.
+///
+/// Each camera with a [`ForceRatio`] component which isn't [`ForceRatio::Disabled`] is given the
+/// lowest free layer from [`ForceRatioLayers::cameras`] for its bars. The layer is added to the
+/// camera's [`RenderLayers`], and removed again along with the component. No other cameras are
+/// changed, so these layers should be reserved for bars.
+#[derive(Clone, Debug, Eq, PartialEq, Resource)]
+pub struct ForceRatioLayers {
+    /// The layer of the bars created for the [`ForceRatio`] resource. Defaults to 0, which every
+    /// camera without [`RenderLayers`] renders.
+    pub resource: Layer,
+    /// The layers given to cameras with a [`ForceRatio`] component. Defaults to `24..32`.
+    pub cameras: Range<Layer>,
+}
+
+impl Default for ForceRatioLayers {
+    fn default() -> Self {
+        Self {
+            resource: 0,
+            cameras: 24..32,
+        }
+    }
+}
+
+/// The camera a [`ForceRatioBar`] belongs to, for bars created for a [`ForceRatio`] component.
+///
+/// These bars are centered on their camera, only rendered by it, and are despawned with it, or
+/// when the component is removed or set to [`ForceRatio::Disabled`].
+#[derive(Component, Clone, Copy)]
+pub struct ForceRatioBarCamera(pub Entity);
+
+const SIDES: [ForceRatioBar; 4] = [
+    ForceRatioBar::Top,
+    ForceRatioBar::Bottom,
+    ForceRatioBar::Left,
+    ForceRatioBar::Right,
+];
+
+fn force_ratio_bar_bundle(
+    side: ForceRatioBar,
+    force_ratio: &ForceRatio,
+    origin: Vec2,
+    layer: Layer,
+) -> impl Bundle {
+    (
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::splat(RATIO_BAR_SIZE)),
+                color: Color::BLACK,
                 ..Default::default()
             },
-            Persistent,
+            visibility: ForceRatioBar::visibility(force_ratio),
+            transform: Transform::from_translation(
+                side.translation(force_ratio) + origin.extend(0.),
+            ),
+            ..Default::default()
+        },
+        RenderLayers::layer(layer),
+        Persistent,
+        side,
+    )
+}
+
+fn force_ratio_setup(
+    mut commands: Commands,
+    force_ratio: Res<ForceRatio>,
+    force_ratio_layers: Res<ForceRatioLayers>,
+) {
+    for side in SIDES {
+        commands.spawn(force_ratio_bar_bundle(
             side,
+            force_ratio.as_ref(),
+            Vec2::ZERO,
+            force_ratio_layers.resource,
         ));
     }
 }
 
+fn force_ratio_camera_setup(
+    mut commands: Commands,
+    camera_query: Query<
+        (Entity, &ForceRatio, &Transform, Option<&RenderLayers>),
+        (With<Camera>, Changed<ForceRatio>),
+    >,
+    camera_layers_query: Query<&RenderLayers, With<Camera>>,
+    bar_query: Query<(Entity, &ForceRatioBarCamera, &RenderLayers)>,
+    resource_bar_query: Query<Entity, (With<ForceRatioBar>, Without<ForceRatioBarCamera>)>,
+    mut removed_force_ratios: RemovedComponents<ForceRatio>,
+    force_ratio_layers: Res<ForceRatioLayers>,
+) {
+    if force_ratio_layers.is_changed() {
+        for bar_entity in resource_bar_query.iter() {
+            commands
+                .entity(bar_entity)
+                .insert(RenderLayers::layer(force_ratio_layers.resource));
+        }
+    }
+    let mut bar_layers: HashMap<Entity, Layer> = bar_query
+        .iter()
+        .filter_map(|(_, bar_camera, layers)| Some((bar_camera.0, layers.iter().next()?)))
+        .collect();
+    let disabled_cameras = camera_query
+        .iter()
+        .filter(|(_, force_ratio, _, _)| **force_ratio == ForceRatio::Disabled)
+        .map(|(camera_entity, _, _, _)| camera_entity);
+    let removed_cameras: Vec<Entity> = removed_force_ratios
+        .iter()
+        .chain(disabled_cameras)
+        .collect();
+    for camera_entity in removed_cameras {
+        let Some(layer) = bar_layers.remove(&camera_entity) else {
+            continue;
+        };
+        for (bar_entity, bar_camera, _) in bar_query.iter() {
+            if bar_camera.0 == camera_entity {
+                commands.entity(bar_entity).despawn_recursive();
+            }
+        }
+        if let Ok(camera_layers) = camera_layers_query.get(camera_entity) {
+            commands
+                .entity(camera_entity)
+                .insert(camera_layers.without(layer));
+        }
+    }
+    for (camera_entity, force_ratio, camera_transform, camera_layers) in camera_query.iter() {
+        if *force_ratio == ForceRatio::Disabled || bar_layers.contains_key(&camera_entity) {
+            continue;
+        }
+        let Some(layer) = force_ratio_layers.cameras.clone().find(|layer| {
+            usize::from(*layer) < RenderLayers::TOTAL_LAYERS
+                && !bar_layers.values().any(|bar_layer| bar_layer == layer)
+        }) else {
+            warn!("no render layers left for the ForceRatio bars of camera {camera_entity:?}");
+            continue;
+        };
+        bar_layers.insert(camera_entity, layer);
+        for side in SIDES {
+            commands.spawn((
+                force_ratio_bar_bundle(
+                    side,
+                    force_ratio,
+                    camera_transform.translation.truncate(),
+                    layer,
+                ),
+                ForceRatioBarCamera(camera_entity),
+            ));
+        }
+        commands
+            .entity(camera_entity)
+            .insert(camera_layers.copied().unwrap_or_default().with(layer));
+    }
+}
+
 fn force_ratio_update(
     mut transform_query: Query<&mut Transform>,
     mut visibility_query: Query<&mut Visibility>,
-    camera_query: Query<Entity, With<Camera>>,
-    bar_query: Query<(Entity, &ForceRatioBar)>,
+    camera_query: Query<(Entity, Option<&ForceRatio>), With<Camera>>,
+    bar_query: Query<(Entity, &ForceRatioBar, Option<&ForceRatioBarCamera>)>,
     window_query: Query<&Window>,
     force_ratio: Res<ForceRatio>,
 ) {
-    if let ForceRatio::Enabled { width, height } = force_ratio.as_ref() {
-        if let Ok(window) = window_query.get_single() {
-            for camera_entity in camera_query.iter() {
+    if let Ok(window) = window_query.get_single() {
+        let physical_size = UVec2::new(window.physical_width(), window.physical_height()).as_vec2();
+        #[allow(clippy::cast_possible_truncation)]
+        let scale_factor = window.scale_factor() as f32;
+        for (camera_entity, camera_force_ratio) in camera_query.iter() {
+            let camera_force_ratio = camera_force_ratio.unwrap_or(force_ratio.as_ref());
+            if let Some(scale) = camera_force_ratio.scale(physical_size, scale_factor) {
                 if let Ok(mut camera_transform) = transform_query.get_mut(camera_entity) {
-                    let ratio = window.width() / window.height();
-                    let mut desired_width = *width;
-                    let mut desired_height = *height;
-                    let desired_ratio = desired_width / desired_height;
-                    if ratio > desired_ratio {
-                        desired_width *= ratio / desired_ratio;
-                    } else {
-                        desired_height *= desired_ratio / ratio;
-                    }
-                    camera_transform.scale.x = desired_width / window.width();
-                    camera_transform.scale.y = desired_height / window.height();
+                    camera_transform.scale.x = scale.x;
+                    camera_transform.scale.y = scale.y;
                 }
             }
         }
     }
-    for (bar_entity, bar) in bar_query.iter() {
+    for (bar_entity, bar, bar_camera) in bar_query.iter() {
+        let (bar_force_ratio, origin) = if let Some(bar_camera) = bar_camera {
+            let camera_force_ratio = camera_query
+                .get(bar_camera.0)
+                .ok()
+                .and_then(|(_, camera_force_ratio)| camera_force_ratio);
+            let camera_translation = transform_query
+                .get(bar_camera.0)
+                .map(|camera_transform| camera_transform.translation);
+            let (Some(camera_force_ratio), Ok(camera_translation)) =
+                (camera_force_ratio, camera_translation)
+            else {
+                // despawned by force_ratio_camera_setup
+                continue;
+            };
+            (camera_force_ratio, camera_translation.truncate())
+        } else {
+            (force_ratio.as_ref(), Vec2::ZERO)
+        };
         if let Ok(mut bar_transform) = transform_query.get_mut(bar_entity) {
-            bar_transform.translation = bar.translation(force_ratio.as_ref());
+            bar_transform.translation = bar.translation(bar_force_ratio) + origin.extend(0.);
         }
         if let Ok(mut bar_visibility) = visibility_query.get_mut(bar_entity) {
-            *bar_visibility = ForceRatioBar::visibility(force_ratio.as_ref());
+            *bar_visibility = ForceRatioBar::visibility(bar_force_ratio);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scale() {
+        let enabled = ForceRatio::Enabled {
+            width: 640.,
+            height: 360.,
+        };
+        let pixel_perfect = ForceRatio::PixelPerfect {
+            width: 320.,
+            height: 180.,
+        };
+        let expand_width = ForceRatio::ExpandWidth { height: 360. };
+        let expand_height = ForceRatio::ExpandHeight { width: 640. };
+        let stretch = ForceRatio::Stretch {
+            width: 640.,
+            height: 360.,
+        };
+        let fill = ForceRatio::Fill {
+            width: 640.,
+            height: 360.,
+        };
+        let cases = [
+            // mode, physical window size, scale factor, scale
+            (ForceRatio::Disabled, (1280., 720.), 1., None),
+            (enabled, (1280., 720.), 1., Some((0.5, 0.5))),
+            (enabled, (1440., 720.), 1., Some((0.5, 0.5))),
+            (enabled, (320., 360.), 1., Some((2., 2.))),
+            (enabled, (321., 181.), 1., Some((640. / 321., 640. / 321.))),
+            (enabled, (1280., 720.), 2., Some((1., 1.))),
+            (pixel_perfect, (320., 180.), 1., Some((1., 1.))),
+            (pixel_perfect, (639., 359.), 1., Some((1., 1.))),
+            (pixel_perfect, (1280., 720.), 1., Some((0.25, 0.25))),
+            (pixel_perfect, (2560., 720.), 1., Some((0.25, 0.25))),
+            (pixel_perfect, (1920., 1080.), 1., Some((1. / 6., 1. / 6.))),
+            (pixel_perfect, (1440., 810.), 1.5, Some((0.375, 0.375))),
+            (
+                pixel_perfect,
+                (1001., 563.),
+                1.25,
+                Some((1.25 / 3., 1.25 / 3.)),
+            ),
+            (pixel_perfect, (100., 100.), 1., Some((1., 1.))),
+            (pixel_perfect, (100., 100.), 2., Some((2., 2.))),
+            (expand_width, (1280., 720.), 1., Some((0.5, 0.5))),
+            (expand_width, (2560., 720.), 1., Some((0.5, 0.5))),
+            (expand_width, (360., 1440.), 1., Some((0.25, 0.25))),
+            (
+                expand_width,
+                (1281., 721.),
+                1.,
+                Some((360. / 721., 360. / 721.)),
+            ),
+            (expand_width, (1280., 720.), 2., Some((1., 1.))),
+            (expand_height, (1280., 720.), 1., Some((0.5, 0.5))),
+            (expand_height, (1280., 2000.), 1., Some((0.5, 0.5))),
+            (expand_height, (2560., 720.), 1., Some((0.25, 0.25))),
+            (expand_height, (1280., 720.), 1.5, Some((0.75, 0.75))),
+            (stretch, (1280., 720.), 1., Some((0.5, 0.5))),
+            (stretch, (2560., 720.), 1., Some((0.25, 0.5))),
+            (stretch, (640., 1440.), 1., Some((1., 0.25))),
+            (
+                stretch,
+                (1279., 721.),
+                2.,
+                Some((1280. / 1279., 720. / 721.)),
+            ),
+            (fill, (1280., 720.), 1., Some((0.5, 0.5))),
+            (fill, (2560., 720.), 1., Some((0.25, 0.25))),
+            (fill, (640., 1440.), 1., Some((0.25, 0.25))),
+            (fill, (1921., 1081.), 1., Some((360. / 1081., 360. / 1081.))),
+            (fill, (2560., 1440.), 1.25, Some((0.3125, 0.3125))),
+        ];
+        for (force_ratio, (width, height), scale_factor, expected) in cases {
+            let scale = force_ratio.scale(Vec2::new(width, height), scale_factor);
+            let expected = expected.map(|(x, y)| Vec2::new(x, y));
+            assert!(
+                match (scale, expected) {
+                    (Some(scale), Some(expected)) => scale.abs_diff_eq(expected, 1e-6),
+                    (scale, expected) => scale == expected,
+                },
+                "{width}x{height} at {scale_factor}: expected {expected:?}, got {scale:?}",
+            );
         }
     }
 }
@@ -1,4 +1,4 @@
-//! Provides the [`ForceRatio`] resource.
+//! Provides the [`ForceRatio`] resource and component.
 //!
 //! Feature flag: `halia_force_ratio`
 
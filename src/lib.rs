@@ -55,12 +55,18 @@ features!(
     ("halia_cursor", cursor, CursorPlugin),
     ("halia_fixed_timestep", fixed_timestep, FixedTimestepPlugin),
     ("halia_force_ratio", force_ratio, ForceRatioPlugin),
+    ("halia_lockstep", lockstep, LockstepPlugin),
+    ("halia_replay", replay, ReplayPlugin),
+    ("halia_rollback", rollback, RollbackPlugin),
     ("halia_sets", sets, SetsPlugin),
     ("halia_sub_assets", sub_assets, SubAssetsPlugin),
     ("halia_time_to_live", time_to_live, TimeToLivePlugin),
     ("halia_transform2", transform2, Transform2Plugin)
 );
 
+#[cfg(feature = "halia_test")]
+pub mod testing;
+
 /// A marker component indicating that an entity must not be automatically despawned by state
 /// transitions or other cleanup systems.
 ///
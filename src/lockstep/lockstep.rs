@@ -0,0 +1,544 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    hash::{Hash, Hasher},
+    mem,
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use bincode::Options;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::fixed_timestep::{
+    is_fixed_update_suspended, suspend_fixed_update, AddFixedEvent, FixedInput, FixedInputSystem,
+    FixedLatchSystem, FixedTimeControlSystem, InputLatch,
+};
+
+use super::{Transport, UdpTransport};
+
+/// System set for lockstep systems.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub enum LockstepSystem {
+    /// Collects local input each frame. Runs in [`CoreSet::PreUpdate`], after
+    /// [`FixedInputSystem`].
+    Collect,
+    /// A [`CoreSet::FixedUpdate`] system which exchanges inputs with peers and runs fixed ticks
+    /// once every player's input has arrived. Runs after the replay and rollback systems, and
+    /// before [`FixedTimeControlSystem`], which has no effect during a session. Sessions wait
+    /// while a replay is playing.
+    Drive,
+}
+
+/// Adds lockstep functionality. Sessions are started by [`Lockstep::start`] for each action type
+/// registered with [`AddLockstep`].
+///
+/// Contained within [`HaliaPlugins`](`crate::HaliaPlugins`).
+pub struct LockstepPlugin;
+
+impl Plugin for LockstepPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LockstepChecksum>()
+            .add_fixed_event::<LockstepDesync>();
+    }
+}
+
+/// A trait implemented by [`App`] allowing inputs to be synchronized between peers.
+pub trait AddLockstep {
+    /// Add a [`Lockstep`] resource which synchronizes [`Input<A>`] between peers. `A` is usually
+    /// an action type registered with [`AddActionMap`](`crate::fixed_timestep::AddActionMap`).
+    fn add_lockstep<A: Copy + Eq + Hash + Send + Sync + Serialize + DeserializeOwned + 'static>(
+        &mut self,
+    ) -> &mut Self;
+}
+
+impl AddLockstep for App {
+    fn add_lockstep<A: Copy + Eq + Hash + Send + Sync + Serialize + DeserializeOwned + 'static>(
+        &mut self,
+    ) -> &mut Self {
+        #[allow(unused_mut)]
+        let mut drive = lockstep_drive::<A>
+            .in_set(LockstepSystem::Drive)
+            .in_base_set(CoreSet::FixedUpdate)
+            .after(FixedLatchSystem)
+            .before(FixedTimeControlSystem);
+        #[cfg(feature = "halia_replay")]
+        {
+            drive = drive.after(crate::replay::ReplaySystem::Drive);
+        }
+        #[cfg(feature = "halia_rollback")]
+        {
+            drive = drive.after(crate::rollback::RollbackSystem::Rollback);
+        }
+        self.init_resource::<Lockstep<A>>()
+            .init_resource::<LockstepChecksum>()
+            .init_resource::<Input<A>>()
+            .add_system(
+                lockstep_collect::<A>
+                    .in_set(LockstepSystem::Collect)
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(FixedInputSystem),
+            )
+            .add_system(drive);
+        self
+    }
+}
+
+/// Synchronizes the input of 2 or more players, so that every peer simulates the same ticks with
+/// the same input.
+///
+/// Each tick, the local player's input is sent to every peer, to be used [`Lockstep::input_delay`]
+/// ticks later. A tick only runs once the input of every player has arrived, so a slow peer slows
+/// everybody down. Fixed timestep systems should read input from [`Lockstep::player`] rather than
+/// [`FixedInput`] while a session is running.
+///
+/// Simulations must be deterministic for peers to stay in sync. To detect desyncs, fixed timestep
+/// systems can add state to [`LockstepChecksum`], which is compared between peers after every
+/// tick. A [`LockstepDesync`] is sent when the checksums differ.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::{lockstep::LoopbackTransport, prelude::*};
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+/// pub enum Action {
+///     Jump,
+/// }
+///
+/// #[derive(Component)]
+/// struct Player(usize);
+///
+/// fn jump(
+///     mut player_query: Query<(&Player, &mut Transform2)>,
+///     mut checksum: ResMut<LockstepChecksum>,
+///     lockstep: Res<Lockstep<Action>>,
+/// ) {
+///     for (player, mut transform) in player_query.iter_mut() {
+///         if lockstep
+///             .player(player.0)
+///             .is_some_and(|input| input.just_pressed(Action::Jump))
+///         {
+///             transform.translation.y += 10.;
+///         }
+///         checksum.add_f32(transform.translation.y);
+///     }
+/// }
+///
+/// let mut app = App::new();
+/// app.add_plugins(HaliaPlugins)
+///     .add_action_map::<Action>()
+///     .add_lockstep::<Action>()
+///     .add_system(jump.in_schedule(CoreSchedule::FixedUpdate));
+///
+/// let transport = LoopbackTransport::group(2).remove(0);
+/// app.world
+///     .resource_mut::<Lockstep<Action>>()
+///     .start(2, 0, transport);
+/// ```
+#[derive(Resource)]
+pub struct Lockstep<A: Copy + Eq + Hash + Send + Sync + 'static> {
+    input_delay: u64,
+    session: Option<Session<A>>,
+}
+
+impl<A: Copy + Eq + Hash + Send + Sync + 'static> Default for Lockstep<A> {
+    fn default() -> Self {
+        Self {
+            input_delay: Self::DEFAULT_INPUT_DELAY,
+            session: None,
+        }
+    }
+}
+
+impl<A: Copy + Eq + Hash + Send + Sync + 'static> Lockstep<A> {
+    /// The default number of ticks between local input and the tick it is used in.
+    pub const DEFAULT_INPUT_DELAY: u64 = 3;
+
+    /// Start a session between `players` peers, where this peer is `local_player`, replacing any
+    /// running session. Every peer must start its session with the same world, number of players
+    /// and [`Lockstep::input_delay`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `local_player` is not less than `players`.
+    pub fn start(&mut self, players: usize, local_player: usize, transport: impl Transport) {
+        assert!(
+            local_player < players,
+            "local player {local_player} is not one of {players} players"
+        );
+        let input_delay = self.input_delay;
+        let mut inputs = BTreeMap::new();
+        for tick in 1..=input_delay {
+            inputs.insert(tick, vec![Some(vec![]); players]);
+        }
+        self.session = Some(Session {
+            transport: Box::new(transport),
+            local_player,
+            input_delay,
+            players: vec![FixedInput::default(); players],
+            tick: 0,
+            sent: input_delay,
+            pending: vec![],
+            inputs,
+            local_inputs: BTreeMap::new(),
+            received: vec![input_delay; players],
+            acks: vec![input_delay; players],
+            checksums: BTreeMap::new(),
+            local_checksums: VecDeque::new(),
+            desyncs: vec![],
+            stalled: false,
+        });
+    }
+
+    /// Stop the running session.
+    pub fn stop(&mut self) {
+        self.session = None;
+    }
+
+    /// Returns `true` if a session is running.
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// Returns `true` if the last frame could not run a tick because a peer's input had not
+    /// arrived yet.
+    #[must_use]
+    pub fn is_stalled(&self) -> bool {
+        self.session.as_ref().is_some_and(|session| session.stalled)
+    }
+
+    /// The number of ticks the running session has simulated.
+    #[must_use]
+    pub fn tick(&self) -> u64 {
+        self.session.as_ref().map_or(0, |session| session.tick)
+    }
+
+    /// The number of players in the running session.
+    #[must_use]
+    pub fn players(&self) -> usize {
+        self.session
+            .as_ref()
+            .map_or(0, |session| session.players.len())
+    }
+
+    /// The player controlled by this peer in the running session.
+    #[must_use]
+    pub fn local_player(&self) -> Option<usize> {
+        self.session.as_ref().map(|session| session.local_player)
+    }
+
+    /// The input of `player` for the current tick, or [`None`] if no session is running or there
+    /// is no such player.
+    #[must_use]
+    pub fn player(&self, player: usize) -> Option<&FixedInput<A>> {
+        self.session.as_ref()?.players.get(player)
+    }
+
+    /// The number of ticks between local input and the tick it is used in. Higher values hide
+    /// more latency, but make the game less responsive.
+    #[must_use]
+    pub fn input_delay(&self) -> u64 {
+        self.input_delay
+    }
+
+    /// Set the number of ticks between local input and the tick it is used in. Takes effect
+    /// when the next session starts.
+    pub fn set_input_delay(&mut self, input_delay: u64) {
+        self.input_delay = input_delay;
+    }
+}
+
+/// A checksum of the simulation, compared between peers after every tick to detect desyncs.
+///
+/// Reset before every tick. Fixed timestep systems add any state which should match between
+/// peers, in the same order on every peer.
+#[derive(Clone, Copy, Debug, Resource)]
+pub struct LockstepChecksum(u64);
+
+impl Default for LockstepChecksum {
+    fn default() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+}
+
+impl Hasher for LockstepChecksum {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(FNV_PRIME);
+        }
+    }
+}
+
+impl LockstepChecksum {
+    /// Add `value` to the checksum.
+    pub fn add(&mut self, value: impl Hash) {
+        value.hash(self);
+    }
+
+    /// Add `value` to the checksum, using its exact bits.
+    pub fn add_f32(&mut self, value: f32) {
+        self.write_u32(value.to_bits());
+    }
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Sent when the [`LockstepChecksum`] of another player differs from the local checksum.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LockstepDesync {
+    /// The tick after which the checksums differed.
+    pub tick: u64,
+    /// The player whose checksum differed.
+    pub player: usize,
+}
+
+/// How many of the latest local checksums are sent with every packet.
+const CHECKSUM_WINDOW: usize = 16;
+
+/// How many ticks checksums are kept for while waiting for other players.
+const CHECKSUM_HISTORY: u64 = 64;
+
+struct Session<A: Copy + Eq + Hash + Send + Sync + 'static> {
+    transport: Box<dyn Transport>,
+    local_player: usize,
+    input_delay: u64,
+    players: Vec<FixedInput<A>>,
+    tick: u64,
+    sent: u64,
+    pending: Vec<InputLatch<A>>,
+    inputs: BTreeMap<u64, Vec<Option<Vec<InputLatch<A>>>>>,
+    local_inputs: BTreeMap<u64, Vec<InputLatch<A>>>,
+    received: Vec<u64>,
+    acks: Vec<u64>,
+    checksums: BTreeMap<u64, Vec<Option<u64>>>,
+    local_checksums: VecDeque<(u64, u64)>,
+    desyncs: Vec<LockstepDesync>,
+    stalled: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Packet<A> {
+    player: usize,
+    acks: Vec<u64>,
+    inputs: Vec<(u64, Vec<InputLatch<A>>)>,
+    checksums: Vec<(u64, u64)>,
+}
+
+impl<A: Copy + Eq + Hash + Send + Sync + Serialize + DeserializeOwned + 'static> Session<A> {
+    fn receive(&mut self) {
+        while let Some((from, bytes)) = self.transport.receive() {
+            let packet = bincode::DefaultOptions::new()
+                .with_limit(UdpTransport::MAX_PACKET_SIZE as u64)
+                .deserialize::<Packet<A>>(&bytes);
+            match packet {
+                Ok(packet) if packet.player == from => self.handle(packet),
+                Ok(packet) => warn!(
+                    "ignored lockstep packet from player {from} claiming to be player {}",
+                    packet.player
+                ),
+                Err(error) => warn!("failed to decode lockstep packet: {error}"),
+            }
+        }
+    }
+
+    fn handle(&mut self, packet: Packet<A>) {
+        let player = packet.player;
+        if player >= self.players.len() || player == self.local_player {
+            return;
+        }
+        if let Some(ack) = packet.acks.get(self.local_player) {
+            self.acks[player] = self.acks[player].max(*ack);
+        }
+        let players = self.players.len();
+        for (tick, latches) in packet.inputs {
+            if tick > self.tick {
+                self.inputs
+                    .entry(tick)
+                    .or_insert_with(|| vec![None; players])[player]
+                    .get_or_insert(latches);
+            }
+        }
+        while self
+            .inputs
+            .get(&(self.received[player] + 1))
+            .is_some_and(|inputs| inputs[player].is_some())
+        {
+            self.received[player] += 1;
+        }
+        for (tick, checksum) in packet.checksums {
+            self.record_checksum(tick, player, checksum);
+        }
+    }
+
+    fn send_local(&mut self, tick: u64) {
+        let latches = mem::take(&mut self.pending);
+        let players = self.players.len();
+        self.inputs
+            .entry(tick)
+            .or_insert_with(|| vec![None; players])[self.local_player] = Some(latches.clone());
+        self.local_inputs.insert(tick, latches);
+        self.received[self.local_player] = tick;
+        self.sent = tick;
+    }
+
+    /// Remove and return the input of every player for `tick`, if it has all arrived.
+    fn take_inputs(&mut self, tick: u64) -> Option<Vec<Vec<InputLatch<A>>>> {
+        if self.received.iter().any(|received| *received < tick) {
+            return None;
+        }
+        self.inputs.remove(&tick)?.into_iter().collect()
+    }
+
+    fn record_checksum(&mut self, tick: u64, player: usize, checksum: u64) {
+        if tick + CHECKSUM_HISTORY <= self.tick {
+            return;
+        }
+        let players = self.players.len();
+        let checksums = self
+            .checksums
+            .entry(tick)
+            .or_insert_with(|| vec![None; players]);
+        // checksums are sent several times, but only compared once
+        if checksums[player].is_some() {
+            return;
+        }
+        checksums[player] = Some(checksum);
+        let Some(local) = checksums[self.local_player] else {
+            return;
+        };
+        for (other, other_checksum) in checksums.iter().enumerate() {
+            let compare = player == self.local_player || other == player;
+            if compare && other_checksum.is_some_and(|other_checksum| other_checksum != local) {
+                self.desyncs.push(LockstepDesync {
+                    tick,
+                    player: other,
+                });
+            }
+        }
+    }
+
+    fn end_tick(&mut self, checksum: u64) {
+        self.tick += 1;
+        for player in &mut self.players {
+            player.advance(self.tick);
+        }
+        self.local_checksums.push_back((self.tick, checksum));
+        if self.local_checksums.len() > CHECKSUM_WINDOW {
+            self.local_checksums.pop_front();
+        }
+        self.record_checksum(self.tick, self.local_player, checksum);
+        let oldest = self.tick.saturating_sub(CHECKSUM_HISTORY);
+        self.checksums.retain(|tick, _| *tick > oldest);
+    }
+
+    fn flush(&mut self) {
+        let acked = (0..self.players.len())
+            .filter(|player| *player != self.local_player)
+            .map(|player| self.acks[player])
+            .min()
+            .unwrap_or(self.sent);
+        self.local_inputs.retain(|tick, _| *tick > acked);
+        let packet = Packet {
+            player: self.local_player,
+            acks: self.received.clone(),
+            inputs: self
+                .local_inputs
+                .iter()
+                .map(|(tick, latches)| (*tick, latches.clone()))
+                .collect(),
+            checksums: self.local_checksums.iter().copied().collect(),
+        };
+        match bincode::DefaultOptions::new().serialize(&packet) {
+            Ok(bytes) => self.transport.send(&bytes),
+            Err(error) => warn!("failed to encode lockstep packet: {error}"),
+        }
+    }
+}
+
+fn lockstep_collect<A: Copy + Eq + Hash + Send + Sync + 'static>(
+    mut lockstep: ResMut<Lockstep<A>>,
+    input: Res<Input<A>>,
+) {
+    let Some(session) = &mut lockstep.session else {
+        return;
+    };
+    for pressed in input.get_just_pressed() {
+        session.pending.push(InputLatch::Press(*pressed));
+    }
+    for released in input.get_just_released() {
+        session.pending.push(InputLatch::Release(*released));
+    }
+}
+
+fn lockstep_drive<A: Copy + Eq + Hash + Send + Sync + Serialize + DeserializeOwned + 'static>(
+    world: &mut World,
+) {
+    if !world.resource::<Lockstep<A>>().is_running()
+        || !world.contains_resource::<FixedTime>()
+        || is_fixed_update_suspended(world)
+    {
+        return;
+    }
+    let delta = world
+        .get_resource::<Time>()
+        .map_or(Duration::ZERO, |time| time.delta());
+    world.resource_mut::<FixedTime>().tick(delta);
+    let mut stalled = false;
+    loop {
+        let fixed_time = world.resource::<FixedTime>();
+        if fixed_time.accumulated() < fixed_time.period {
+            break;
+        }
+        let mut lockstep = world.resource_mut::<Lockstep<A>>();
+        let Some(session) = &mut lockstep.session else {
+            return;
+        };
+        session.receive();
+        let next = session.tick + 1;
+        if session.sent < next + session.input_delay {
+            session.send_local(next + session.input_delay);
+        }
+        let Some(inputs) = session.take_inputs(next) else {
+            stalled = true;
+            break;
+        };
+        for (player, latches) in session.players.iter_mut().zip(inputs) {
+            for latch in latches {
+                player.latch(latch);
+            }
+        }
+        let _ = world.resource_mut::<FixedTime>().expend();
+        *world.resource_mut::<LockstepChecksum>() = LockstepChecksum::default();
+        world.run_schedule(CoreSchedule::FixedUpdate);
+        let checksum = world.resource::<LockstepChecksum>().finish();
+        if let Some(session) = &mut world.resource_mut::<Lockstep<A>>().session {
+            session.end_tick(checksum);
+        }
+    }
+    if stalled {
+        // don't build up time while waiting, otherwise every tick would run at once when the
+        // input arrives
+        let mut fixed_time = world.resource_mut::<FixedTime>();
+        let period = fixed_time.period;
+        *fixed_time = FixedTime::new(period);
+        fixed_time.tick(period);
+    }
+    let mut lockstep = world.resource_mut::<Lockstep<A>>();
+    let desyncs = if let Some(session) = &mut lockstep.session {
+        session.stalled = stalled;
+        session.receive();
+        session.flush();
+        mem::take(&mut session.desyncs)
+    } else {
+        vec![]
+    };
+    for desync in desyncs {
+        world.send_event(desync);
+    }
+    suspend_fixed_update(world);
+}
@@ -0,0 +1,19 @@
+//! Synchronizes fixed timestep input between peers for online multiplayer.
+//!
+//! - [`Lockstep`]
+//! - [`LockstepChecksum`]
+//! - [`AddLockstep`]
+//! - [`Transport`], with [`LoopbackTransport`] and [`UdpTransport`]
+//!
+//! Feature flag: `halia_lockstep`
+
+mod lockstep;
+mod transport;
+
+pub use lockstep::*;
+pub use transport::*;
+
+#[doc(hidden)]
+pub mod prelude {
+    pub use super::{AddLockstep, Lockstep, LockstepChecksum, LockstepDesync};
+}
@@ -0,0 +1,142 @@
+use std::{
+    collections::VecDeque,
+    io::{self, ErrorKind},
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+    sync::{Arc, Mutex},
+};
+
+use bevy::prelude::*;
+
+/// Sends packets between the peers of a [`Lockstep`](`super::Lockstep`) session.
+///
+/// Packets may be lost, duplicated or reordered, since lockstep resends anything which has not
+/// been acknowledged.
+pub trait Transport: Send + Sync + 'static {
+    /// Send `packet` to every other peer.
+    fn send(&mut self, packet: &[u8]);
+
+    /// Receive the next packet from any peer, along with the player whose peer sent it, or
+    /// [`None`] if no packets are waiting. Must not block.
+    ///
+    /// The player must be identified by the transport itself, such as by the address the packet
+    /// came from, since the contents of a packet can't be trusted.
+    fn receive(&mut self) -> Option<(usize, Vec<u8>)>;
+}
+
+/// A [`Transport`] connecting peers within the same process, useful for tests and local
+/// multiplayer. Each peer is the player with the same index.
+///
+/// ```
+/// # use halia::lockstep::{LoopbackTransport, Transport};
+/// let [mut a, mut b] = <[LoopbackTransport; 2]>::try_from(LoopbackTransport::group(2)).unwrap();
+/// a.send(b"hello");
+/// assert_eq!(b.receive(), Some((0, b"hello".to_vec())));
+/// assert_eq!(a.receive(), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LoopbackTransport {
+    peer: usize,
+    queues: Arc<Mutex<Vec<VecDeque<(usize, Vec<u8>)>>>>,
+}
+
+impl LoopbackTransport {
+    /// Create a transport for each of `peers` peers, all connected to each other.
+    #[must_use]
+    pub fn group(peers: usize) -> Vec<Self> {
+        let queues = Arc::new(Mutex::new(vec![VecDeque::new(); peers]));
+        (0..peers)
+            .map(|peer| Self {
+                peer,
+                queues: queues.clone(),
+            })
+            .collect()
+    }
+}
+
+impl Transport for LoopbackTransport {
+    fn send(&mut self, packet: &[u8]) {
+        let Ok(mut queues) = self.queues.lock() else {
+            return;
+        };
+        for (peer, queue) in queues.iter_mut().enumerate() {
+            if peer != self.peer {
+                queue.push_back((self.peer, packet.to_vec()));
+            }
+        }
+    }
+
+    fn receive(&mut self) -> Option<(usize, Vec<u8>)> {
+        self.queues.lock().ok()?.get_mut(self.peer)?.pop_front()
+    }
+}
+
+/// A [`Transport`] sending packets to a fixed list of peers over UDP.
+///
+/// Packets are attributed to the player whose address they came from. Packets from addresses
+/// which are not peers are ignored.
+#[derive(Debug)]
+pub struct UdpTransport {
+    socket: UdpSocket,
+    peers: Vec<(usize, SocketAddr)>,
+    buffer: Vec<u8>,
+}
+
+impl UdpTransport {
+    /// The largest packet which can be received.
+    pub const MAX_PACKET_SIZE: usize = 65_507;
+
+    /// Bind a non-blocking UDP socket to `address`, which exchanges packets with `peers`. Each
+    /// peer is a player and the address that player's packets are sent to and received from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket could not be bound or made non-blocking.
+    pub fn bind(
+        address: impl ToSocketAddrs,
+        peers: impl IntoIterator<Item = (usize, SocketAddr)>,
+    ) -> io::Result<Self> {
+        let socket = UdpSocket::bind(address)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            peers: peers.into_iter().collect(),
+            buffer: vec![0; Self::MAX_PACKET_SIZE],
+        })
+    }
+
+    /// The address the socket is bound to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address could not be read from the socket.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+}
+
+impl Transport for UdpTransport {
+    fn send(&mut self, packet: &[u8]) {
+        for (_, peer) in &self.peers {
+            if let Err(error) = self.socket.send_to(packet, peer) {
+                warn!("failed to send lockstep packet to {peer}: {error}");
+            }
+        }
+    }
+
+    fn receive(&mut self) -> Option<(usize, Vec<u8>)> {
+        loop {
+            match self.socket.recv_from(&mut self.buffer) {
+                Ok((len, from)) => {
+                    if let Some((player, _)) = self.peers.iter().find(|(_, peer)| *peer == from) {
+                        return Some((*player, self.buffer[..len].to_vec()));
+                    }
+                }
+                Err(error) if error.kind() == ErrorKind::WouldBlock => return None,
+                Err(error) => {
+                    warn!("failed to receive lockstep packet: {error}");
+                    return None;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! Provides deterministic recording and playback of fixed timestep systems.
+//!
+//! - [`Replay`]
+//! - [`Recording`]
+//! - [`AddReplay`]
+//!
+//! Feature flag: `halia_replay`
+
+mod recording;
+mod replay;
+
+pub use recording::*;
+pub use replay::*;
+
+#[doc(hidden)]
+pub mod prelude {
+    pub use super::{AddReplay, Recording, Replay};
+}
@@ -0,0 +1,139 @@
+use std::{
+    fmt::{self, Display},
+    fs,
+    path::Path,
+    time::Duration,
+};
+
+use bincode::Options;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// An error which occurred while saving or loading a [`Recording`].
+#[derive(Debug)]
+pub enum ReplayError {
+    /// Reading or writing the file failed.
+    Io(std::io::Error),
+    /// Encoding or decoding the recording failed.
+    Encoding(bincode::Error),
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "replay io error: {error}"),
+            Self::Encoding(error) => write!(f, "replay encoding error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<std::io::Error> for ReplayError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<bincode::Error> for ReplayError {
+    fn from(error: bincode::Error) -> Self {
+        Self::Encoding(error)
+    }
+}
+
+/// A recording of every fixed tick, created by
+/// [`Replay::start_recording`](`super::Replay::start_recording`) and played back with
+/// [`Replay::play`](`super::Replay::play`).
+///
+/// Each tick stores the data of every registered channel (see
+/// [`AddReplay`](`super::AddReplay`)). Channels without new data for a tick take up a single
+/// byte.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Recording {
+    pub(crate) period: Duration,
+    pub(crate) start_tick: u64,
+    pub(crate) channels: Vec<String>,
+    pub(crate) ticks: Vec<Vec<Vec<u8>>>,
+}
+
+impl Recording {
+    /// The largest encoded size of a recording, in bytes. Larger recordings fail to encode, and
+    /// corrupt data claiming to be larger fails to decode instead of allocating unbounded memory.
+    pub const MAX_SIZE: u64 = 256 * 1024 * 1024;
+
+    /// The number of ticks in this recording.
+    #[must_use]
+    pub fn len(&self) -> u64 {
+        self.ticks.len() as u64
+    }
+
+    /// Returns `true` if this recording has no ticks.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ticks.is_empty()
+    }
+
+    /// The [`FixedTime::period`](`bevy::prelude::FixedTime::period`) this recording was made
+    /// with.
+    #[must_use]
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// The [`FixedTick`](`crate::fixed_timestep::FixedTick`) before the first tick of this
+    /// recording, which is restored when it is played back.
+    #[must_use]
+    pub fn start_tick(&self) -> u64 {
+        self.start_tick
+    }
+
+    /// Encode this recording into bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReplayError::Encoding`] if the recording could not be encoded, or is larger than
+    /// [`Recording::MAX_SIZE`].
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ReplayError> {
+        Ok(encode(self)?)
+    }
+
+    /// Decode a recording from bytes created with [`Recording::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReplayError::Encoding`] if the bytes are not a valid recording, or are larger
+    /// than [`Recording::MAX_SIZE`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReplayError> {
+        Ok(decode(bytes)?)
+    }
+
+    /// Save this recording to a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ReplayError`] if the recording could not be encoded or written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ReplayError> {
+        fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Load a recording from a file created with [`Recording::save`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ReplayError`] if the file could not be read or is not a valid recording.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
+
+pub(crate) fn encode<T: Serialize>(value: &T) -> bincode::Result<Vec<u8>> {
+    options().serialize(value)
+}
+
+pub(crate) fn decode<T: DeserializeOwned>(bytes: &[u8]) -> bincode::Result<T> {
+    options().deserialize(bytes)
+}
+
+fn options() -> impl Options {
+    bincode::DefaultOptions::new().with_limit(Recording::MAX_SIZE)
+}
@@ -0,0 +1,617 @@
+use std::{any::type_name, hash::Hash, marker::PhantomData, mem, time::Duration};
+
+use bevy::{ecs::event::ManualEventReader, prelude::*};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::fixed_timestep::{
+    suspend_fixed_update, FixedAxis, FixedInput, FixedInputSnapshot, FixedInputSystem,
+    FixedLatchSystem, FixedSet, FixedTextInput, FixedTick, FixedTimeControlSystem, InputLatch,
+    MouseAxis,
+};
+
+use super::{decode, encode, Recording};
+
+/// System set for replay systems.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub enum ReplaySystem {
+    /// A [`CoreSet::FixedUpdate`] system which runs fixed ticks during playback, respecting
+    /// pause, seek and speed. Runs before the rollback and lockstep systems, and before
+    /// [`FixedTimeControlSystem`], which have no effect during playback.
+    Drive,
+    /// Starts recording or playing back a tick. Runs in [`FixedSet::First`].
+    Begin,
+    /// Records or plays back each registered channel. Runs in [`FixedSet::First`], after
+    /// [`ReplaySystem::Begin`].
+    ///
+    /// Fixed timestep systems in [`FixedSet::First`] which read replayed data should run after
+    /// this set.
+    Channel,
+    /// Finishes playing back a tick. Runs in [`FixedSet::Last`].
+    End,
+}
+
+/// Adds replay functionality, controlled with the [`Replay`] resource.
+///
+/// Records [`FixedInput`] for [`KeyCode`], [`ScanCode`], [`MouseButton`], and [`GamepadButton`],
+/// [`FixedAxis`] for [`GamepadAxis`], [`GamepadButton`] and [`MouseAxis`], and
+/// [`FixedTextInput`], as well as [`Cursor`](`crate::cursor::Cursor`) if `halia_cursor` is
+/// enabled.
+///
+/// Contained within [`HaliaPlugins`](`crate::HaliaPlugins`).
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Replay>()
+            .init_resource::<ReplayChannels>()
+            .init_resource::<FixedTick>()
+            .configure_set(FixedInputSystem.run_if(replay_not_playing))
+            .add_system(
+                replay_drive
+                    .in_set(ReplaySystem::Drive)
+                    .in_base_set(CoreSet::FixedUpdate)
+                    .after(FixedLatchSystem)
+                    .before(FixedTimeControlSystem),
+            )
+            .add_systems(
+                (
+                    replay_begin
+                        .in_set(ReplaySystem::Begin)
+                        .in_base_set(FixedSet::First),
+                    replay_end
+                        .in_set(ReplaySystem::End)
+                        .in_base_set(FixedSet::Last),
+                )
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+                schedule.configure_set(ReplaySystem::Begin.before(ReplaySystem::Channel));
+            })
+            .add_replay_input::<KeyCode>()
+            .add_replay_input::<ScanCode>()
+            .add_replay_input::<MouseButton>()
+            .add_replay_input::<GamepadButton>()
+            .add_replay_resource::<FixedAxis<GamepadAxis>>()
+            .add_replay_resource::<FixedAxis<GamepadButton>>()
+            .add_replay_resource::<FixedAxis<MouseAxis>>()
+            .add_replay_resource::<FixedTextInput>();
+        #[cfg(feature = "halia_cursor")]
+        app.add_replay_resource::<crate::cursor::Cursor>();
+    }
+}
+
+/// A trait implemented by [`App`] allowing additional data to be recorded by [`Replay`].
+///
+/// Each registration adds a channel to recordings. A recording can only be played back by an app
+/// which registered the same channels in the same order.
+pub trait AddReplay {
+    /// Record presses and releases of [`FixedInput<T>`]. This is called automatically for
+    /// [`KeyCode`], [`ScanCode`], [`MouseButton`], and [`GamepadButton`].
+    ///
+    /// [`FixedInput<T>`] must be registered (see
+    /// [`AddFixedInput`](`crate::fixed_timestep::AddFixedInput`)).
+    fn add_replay_input<
+        T: Copy + Eq + Hash + Send + Sync + Serialize + DeserializeOwned + 'static,
+    >(
+        &mut self,
+    ) -> &mut Self;
+
+    /// Record events of type `E` sent outside of fixed timestep systems. During playback, these
+    /// events are discarded and replaced by the recorded events.
+    ///
+    /// `E` must be registered with [`AddFixedEvent`](`crate::fixed_timestep::AddFixedEvent`).
+    fn add_replay_event<E: Event + Clone + Serialize + DeserializeOwned>(&mut self) -> &mut Self;
+
+    /// Record the value of resource `R` seen by each tick. During playback, the recorded value is
+    /// restored at the start of each tick. Nothing is recorded while `R` does not exist.
+    ///
+    /// Axes added with [`AddFixedAxis`](`crate::fixed_timestep::AddFixedAxis`) are recorded by
+    /// registering their [`FixedAxis`].
+    fn add_replay_resource<R: Resource + Clone + Serialize + DeserializeOwned>(
+        &mut self,
+    ) -> &mut Self;
+}
+
+impl AddReplay for App {
+    fn add_replay_input<
+        T: Copy + Eq + Hash + Send + Sync + Serialize + DeserializeOwned + 'static,
+    >(
+        &mut self,
+    ) -> &mut Self {
+        if register_channel::<FixedInput<T>>(self) {
+            self.add_system(
+                replay_input::<T>
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(ReplaySystem::Channel)
+                    .in_base_set(FixedSet::First),
+            );
+        }
+        self
+    }
+
+    fn add_replay_event<E: Event + Clone + Serialize + DeserializeOwned>(&mut self) -> &mut Self {
+        if register_channel::<Events<E>>(self) {
+            self.init_resource::<ReplayEvents<E>>()
+                .add_systems(
+                    (
+                        replay_event_collect::<E>.before(ReplaySystem::Drive),
+                        replay_event_skip::<E>.after(FixedTimeControlSystem),
+                    )
+                        .in_base_set(CoreSet::FixedUpdate),
+                )
+                .add_system(
+                    replay_event::<E>
+                        .in_schedule(CoreSchedule::FixedUpdate)
+                        .in_set(ReplaySystem::Channel)
+                        .in_base_set(FixedSet::First),
+                );
+        }
+        self
+    }
+
+    fn add_replay_resource<R: Resource + Clone + Serialize + DeserializeOwned>(
+        &mut self,
+    ) -> &mut Self {
+        if register_channel::<R>(self) {
+            self.init_resource::<ReplayResource<R>>().add_system(
+                replay_resource::<R>
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(ReplaySystem::Channel)
+                    .in_base_set(FixedSet::First),
+            );
+        }
+        self
+    }
+}
+
+/// The current state of a [`Replay`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum ReplayMode {
+    /// Not recording or playing back.
+    #[default]
+    Idle,
+    /// Recording each tick.
+    Recording,
+    /// Playing back a recording. Live input is ignored.
+    Playback,
+}
+
+/// Records fixed timestep input and plays it back deterministically.
+///
+/// Recording captures, for every tick of [`CoreSchedule::FixedUpdate`], everything registered
+/// with [`AddReplay`]. Playing the recording back from the same starting state reproduces the same
+/// ticks, which is useful for bug reproduction and automated regression runs.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::prelude::*;
+/// fn toggle_recording(mut replay: ResMut<Replay>, keys: Res<Input<KeyCode>>) {
+///     if keys.just_pressed(KeyCode::F9) {
+///         if replay.is_recording() {
+///             replay.stop();
+///             replay.recording().save("bug.replay").unwrap();
+///         } else {
+///             replay.start_recording();
+///         }
+///     }
+/// }
+/// # bevy::ecs::system::assert_is_system(toggle_recording);
+/// ```
+#[derive(Debug, Resource)]
+pub struct Replay {
+    mode: ReplayMode,
+    recording: Recording,
+    playhead: u64,
+    paused: bool,
+    speed: f32,
+    seek: Option<u64>,
+    accumulated: Duration,
+}
+
+impl Default for Replay {
+    fn default() -> Self {
+        Self {
+            mode: ReplayMode::Idle,
+            recording: Recording::default(),
+            playhead: 0,
+            paused: false,
+            speed: 1.,
+            seek: None,
+            accumulated: Duration::ZERO,
+        }
+    }
+}
+
+impl Replay {
+    /// The current [`ReplayMode`].
+    #[must_use]
+    pub fn mode(&self) -> ReplayMode {
+        self.mode
+    }
+
+    /// Returns `true` if ticks are being recorded.
+    #[must_use]
+    pub fn is_recording(&self) -> bool {
+        self.mode == ReplayMode::Recording
+    }
+
+    /// Returns `true` if a recording is being played back.
+    #[must_use]
+    pub fn is_playing(&self) -> bool {
+        self.mode == ReplayMode::Playback
+    }
+
+    /// Start a new recording, beginning with the next tick. Discards the previous recording.
+    pub fn start_recording(&mut self) {
+        self.mode = ReplayMode::Recording;
+        self.recording = Recording::default();
+        self.playhead = 0;
+    }
+
+    /// Play back `recording`, beginning with the next tick.
+    ///
+    /// The world should be in the same state it was in when the recording started, otherwise the
+    /// playback will diverge. [`FixedTick`] is restored automatically.
+    pub fn play(&mut self, recording: Recording) {
+        self.mode = ReplayMode::Playback;
+        self.recording = recording;
+        self.playhead = 0;
+        self.paused = false;
+        self.seek = None;
+        self.accumulated = Duration::ZERO;
+    }
+
+    /// Stop recording or playing back.
+    pub fn stop(&mut self) {
+        self.mode = ReplayMode::Idle;
+        self.seek = None;
+    }
+
+    /// The current recording, either being recorded or played back.
+    #[must_use]
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Take the current recording, leaving an empty recording in its place. Stops recording or
+    /// playing back.
+    pub fn take_recording(&mut self) -> Recording {
+        self.stop();
+        mem::take(&mut self.recording)
+    }
+
+    /// The next tick to be recorded or played back.
+    #[must_use]
+    pub fn tick(&self) -> u64 {
+        match self.mode {
+            ReplayMode::Recording => self.recording.len(),
+            ReplayMode::Idle | ReplayMode::Playback => self.playhead,
+        }
+    }
+
+    /// Returns `true` if playback is paused.
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pause playback. No fixed ticks run while paused.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resume playback.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Run ticks as fast as possible until `tick` is reached, even if paused. Returns `false` if
+    /// `tick` has already been played back or is past the end of the recording.
+    ///
+    /// Seeking backwards is not supported, since it would require restoring the world to an
+    /// earlier state. Instead, restore the starting state and play the recording again.
+    pub fn seek(&mut self, tick: u64) -> bool {
+        if self.mode != ReplayMode::Playback || tick <= self.playhead || tick > self.recording.len()
+        {
+            return false;
+        }
+        self.seek = Some(tick);
+        true
+    }
+
+    /// The playback speed multiplier.
+    #[must_use]
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Set the playback speed multiplier. For example, `4.` fast-forwards at four times the
+    /// recorded speed.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.max(0.);
+    }
+
+    fn write<D: Serialize>(&mut self, channel: usize, data: &D) {
+        let Some(tick) = self.recording.ticks.last_mut() else {
+            return;
+        };
+        match encode(data) {
+            Ok(bytes) => tick[channel] = bytes,
+            Err(error) => warn!("failed to record replay channel {channel}: {error}"),
+        }
+    }
+
+    fn read<D: DeserializeOwned>(&self, channel: usize) -> Option<D> {
+        let bytes = self
+            .recording
+            .ticks
+            .get(usize::try_from(self.playhead).ok()?)?
+            .get(channel)?;
+        if bytes.is_empty() {
+            return None;
+        }
+        match decode(bytes) {
+            Ok(data) => Some(data),
+            Err(error) => {
+                warn!("failed to play back replay channel {channel}: {error}");
+                None
+            }
+        }
+    }
+}
+
+#[derive(Default, Resource)]
+struct ReplayChannels {
+    names: Vec<String>,
+}
+
+#[derive(Resource)]
+struct ReplayChannel<C> {
+    index: usize,
+    _marker: PhantomData<fn() -> C>,
+}
+
+fn register_channel<C: 'static>(app: &mut App) -> bool {
+    if app.world.contains_resource::<ReplayChannel<C>>() {
+        return false;
+    }
+    let mut channels = app
+        .world
+        .get_resource_or_insert_with(ReplayChannels::default);
+    let index = channels.names.len();
+    channels.names.push(type_name::<C>().to_owned());
+    app.insert_resource(ReplayChannel::<C> {
+        index,
+        _marker: PhantomData,
+    });
+    true
+}
+
+#[derive(Deserialize, Serialize)]
+struct InputTick<T: Copy + Eq + Hash + Send + Sync + 'static> {
+    snapshot: Option<FixedInputSnapshot<T>>,
+    latched: Vec<InputLatch<T>>,
+}
+
+#[derive(Resource)]
+struct ReplayEvents<E: Event> {
+    reader: ManualEventReader<E>,
+    pending: Vec<E>,
+}
+
+impl<E: Event> Default for ReplayEvents<E> {
+    fn default() -> Self {
+        Self {
+            reader: ManualEventReader::default(),
+            pending: vec![],
+        }
+    }
+}
+
+#[derive(Resource)]
+struct ReplayResource<R: Resource> {
+    last: Option<R>,
+}
+
+impl<R: Resource> Default for ReplayResource<R> {
+    fn default() -> Self {
+        Self { last: None }
+    }
+}
+
+fn replay_not_playing(replay: Res<Replay>) -> bool {
+    !replay.is_playing()
+}
+
+fn replay_drive(world: &mut World) {
+    if !world.resource::<Replay>().is_playing() || !suspend_fixed_update(world) {
+        return;
+    }
+    let replay = world.resource::<Replay>();
+    let period = replay.recording.period;
+    let delta = if replay.paused {
+        Duration::ZERO
+    } else {
+        world.resource::<Time>().delta().mul_f32(replay.speed)
+    };
+    let mut replay = world.resource_mut::<Replay>();
+    let mut ticks = 0;
+    if let Some(seek) = replay.seek.take() {
+        ticks += seek.saturating_sub(replay.playhead);
+    }
+    if !period.is_zero() {
+        replay.accumulated += delta;
+        while replay.accumulated >= period {
+            replay.accumulated -= period;
+            ticks += 1;
+        }
+    }
+    world.resource_mut::<FixedTime>().period = period;
+    for _ in 0..ticks {
+        if !world.resource::<Replay>().is_playing() {
+            break;
+        }
+        world.run_schedule(CoreSchedule::FixedUpdate);
+    }
+    world.resource_mut::<FixedTime>().period = Duration::MAX;
+}
+
+fn replay_begin(
+    mut replay: ResMut<Replay>,
+    channels: Res<ReplayChannels>,
+    fixed_time: Res<FixedTime>,
+    mut fixed_tick: ResMut<FixedTick>,
+) {
+    match replay.mode {
+        ReplayMode::Recording => {
+            if replay.recording.is_empty() {
+                replay.recording.period = fixed_time.period;
+                replay.recording.start_tick = fixed_tick.0;
+                replay.recording.channels.clone_from(&channels.names);
+            }
+            replay
+                .recording
+                .ticks
+                .push(vec![vec![]; channels.names.len()]);
+        }
+        ReplayMode::Playback => {
+            if replay.playhead == 0 {
+                if replay.recording.channels == channels.names {
+                    fixed_tick.0 = replay.recording.start_tick;
+                } else {
+                    warn!("recording channels do not match the registered replay channels");
+                    replay.stop();
+                }
+            }
+        }
+        ReplayMode::Idle => {}
+    }
+}
+
+fn replay_end(mut replay: ResMut<Replay>) {
+    if replay.mode == ReplayMode::Playback {
+        replay.playhead += 1;
+        if replay.playhead >= replay.recording.len() {
+            replay.stop();
+        }
+    }
+}
+
+fn replay_input<T: Copy + Eq + Hash + Send + Sync + Serialize + DeserializeOwned + 'static>(
+    mut replay: ResMut<Replay>,
+    mut fixed_input: ResMut<FixedInput<T>>,
+    channel: Res<ReplayChannel<FixedInput<T>>>,
+) {
+    match replay.mode {
+        ReplayMode::Recording => {
+            // the first tick stores the full state, every other tick only stores changes
+            if replay.recording.len() == 1 {
+                let input_tick = InputTick {
+                    snapshot: Some(fixed_input.snapshot()),
+                    latched: vec![],
+                };
+                replay.write(channel.index, &input_tick);
+            } else if !fixed_input.latched().is_empty() {
+                let input_tick = InputTick {
+                    snapshot: None,
+                    latched: fixed_input.latched().to_vec(),
+                };
+                replay.write(channel.index, &input_tick);
+            }
+        }
+        ReplayMode::Playback => {
+            if let Some(input_tick) = replay.read::<InputTick<T>>(channel.index) {
+                if let Some(snapshot) = input_tick.snapshot {
+                    fixed_input.restore(snapshot);
+                }
+                for latch in input_tick.latched {
+                    fixed_input.latch(latch);
+                }
+            }
+        }
+        ReplayMode::Idle => {}
+    }
+}
+
+fn replay_event_collect<E: Event + Clone>(
+    mut replay_events: ResMut<ReplayEvents<E>>,
+    mut events: ResMut<Events<E>>,
+    replay: Res<Replay>,
+) {
+    let ReplayEvents { reader, pending } = replay_events.as_mut();
+    match replay.mode {
+        ReplayMode::Recording => {
+            pending.extend(reader.iter(&events).cloned());
+        }
+        ReplayMode::Playback => {
+            events.clear();
+            reader.clear(&events);
+            pending.clear();
+        }
+        ReplayMode::Idle => {
+            reader.clear(&events);
+            pending.clear();
+        }
+    }
+}
+
+fn replay_event_skip<E: Event>(mut replay_events: ResMut<ReplayEvents<E>>, events: Res<Events<E>>) {
+    // events sent by fixed timestep systems are reproduced by playback, so don't record them
+    replay_events.reader.clear(&events);
+}
+
+fn replay_event<E: Event + Clone + Serialize + DeserializeOwned>(
+    mut replay: ResMut<Replay>,
+    mut replay_events: ResMut<ReplayEvents<E>>,
+    mut events: ResMut<Events<E>>,
+    channel: Res<ReplayChannel<Events<E>>>,
+) {
+    match replay.mode {
+        ReplayMode::Recording => {
+            if !replay_events.pending.is_empty() {
+                let pending = mem::take(&mut replay_events.pending);
+                replay.write(channel.index, &pending);
+            }
+        }
+        ReplayMode::Playback => {
+            if let Some(recorded) = replay.read::<Vec<E>>(channel.index) {
+                for event in recorded {
+                    events.send(event);
+                }
+            }
+        }
+        ReplayMode::Idle => {}
+    }
+}
+
+fn replay_resource<R: Resource + Clone + Serialize + DeserializeOwned>(
+    mut replay: ResMut<Replay>,
+    resource: Option<ResMut<R>>,
+    mut replay_resource: ResMut<ReplayResource<R>>,
+    channel: Res<ReplayChannel<R>>,
+) {
+    let Some(mut resource) = resource else {
+        return;
+    };
+    match replay.mode {
+        ReplayMode::Recording => {
+            if replay.recording.len() == 1 || resource.is_changed() {
+                replay.write(channel.index, resource.as_ref());
+            }
+        }
+        ReplayMode::Playback => {
+            if let Some(recorded) = replay.read::<R>(channel.index) {
+                replay_resource.last = Some(recorded);
+            }
+            // frame rate systems may have changed the resource since the last tick
+            if let Some(last) = &replay_resource.last {
+                *resource = last.clone();
+            }
+        }
+        ReplayMode::Idle => {
+            if replay_resource.last.is_some() {
+                replay_resource.last = None;
+            }
+        }
+    }
+}
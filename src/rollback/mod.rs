@@ -0,0 +1,17 @@
+//! Provides snapshots of the world which can be rolled back and re-simulated, for rollback
+//! netcode and rewind mechanics.
+//!
+//! - [`Snapshots`]
+//! - [`Rollback`]
+//! - [`RollbackId`]
+//! - [`AddRollback`]
+//!
+//! Feature flag: `halia_rollback`
+
+mod rollback;
+pub use rollback::*;
+
+#[doc(hidden)]
+pub mod prelude {
+    pub use super::{AddRollback, Rollback, RollbackId, Snapshots};
+}
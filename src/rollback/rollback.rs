@@ -0,0 +1,515 @@
+use std::{
+    any::{Any, TypeId},
+    collections::{HashMap, HashSet, VecDeque},
+};
+
+use bevy::{hierarchy::despawn_with_children_recursive, prelude::*};
+
+use crate::{
+    fixed_timestep::{
+        is_fixed_update_suspended, FixedInput, FixedLatchSystem, FixedRng, FixedSet, FixedTick,
+        FixedTickSystem, FixedTimeControlSystem,
+    },
+    transform2::{Depth, Transform2},
+};
+
+/// System set for rollback systems.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub enum RollbackSystem {
+    /// A [`CoreSet::FixedUpdate`] system which performs rollbacks requested with
+    /// [`Snapshots::rollback_to`]. Runs after the replay system and before the lockstep systems
+    /// and [`FixedTimeControlSystem`]. Rollbacks wait while a replay is playing.
+    Rollback,
+    /// Takes a snapshot at the start of each tick. Runs in [`FixedSet::First`], before
+    /// [`FixedTickSystem`].
+    Snapshot,
+}
+
+/// Adds rollback functionality, controlled with the [`Snapshots`] resource.
+///
+/// Snapshots [`Transform2`], [`Transform`] and [`Depth`] of every [`Rollback`] entity, as well as
+/// [`TimeToLive`](`crate::time_to_live::TimeToLive`) if `halia_time_to_live` is enabled, along
+/// with the [`FixedTick`] and [`FixedRng`] resources.
+/// [`FixedInput`] for [`KeyCode`], [`ScanCode`], [`MouseButton`], and [`GamepadButton`] is
+/// restored while re-simulating, as well as [`Cursor`](`crate::cursor::Cursor`) if `halia_cursor`
+/// is enabled.
+///
+/// Contained within [`HaliaPlugins`](`crate::HaliaPlugins`).
+pub struct RollbackPlugin;
+
+impl Plugin for RollbackPlugin {
+    fn build(&self, app: &mut App) {
+        #[allow(unused_mut)]
+        let mut drive = rollback_drive
+            .in_set(RollbackSystem::Rollback)
+            .in_base_set(CoreSet::FixedUpdate)
+            .after(FixedLatchSystem)
+            .before(FixedTimeControlSystem);
+        #[allow(unused_mut)]
+        let mut snapshot = rollback_snapshot
+            .in_set(RollbackSystem::Snapshot)
+            .in_base_set(FixedSet::First)
+            .before(FixedTickSystem);
+        #[cfg(feature = "halia_replay")]
+        {
+            drive = drive.after(crate::replay::ReplaySystem::Drive);
+            snapshot = snapshot.after(crate::replay::ReplaySystem::Channel);
+        }
+        app.init_resource::<Snapshots>()
+            .add_system(drive)
+            .add_system(snapshot.in_schedule(CoreSchedule::FixedUpdate))
+            .add_rollback_component::<Transform2>()
+            .add_rollback_component::<Transform>()
+            .add_rollback_component::<Depth>()
+            .add_rollback_resource::<FixedTick>()
+            .add_rollback_resource::<FixedRng>()
+            .add_rollback_input::<FixedInput<KeyCode>>()
+            .add_rollback_input::<FixedInput<ScanCode>>()
+            .add_rollback_input::<FixedInput<MouseButton>>()
+            .add_rollback_input::<FixedInput<GamepadButton>>();
+        #[cfg(feature = "halia_time_to_live")]
+        app.add_rollback_component::<crate::time_to_live::TimeToLive>();
+        #[cfg(feature = "halia_cursor")]
+        app.add_rollback_input::<crate::cursor::Cursor>();
+    }
+}
+
+/// A trait implemented by [`App`] allowing additional data to be saved by [`Snapshots`].
+pub trait AddRollback {
+    /// Save component `C` of every [`Rollback`] entity. On rollback, `C` is restored, inserted or
+    /// removed so that each entity matches the snapshot.
+    fn add_rollback_component<C: Component + Clone>(&mut self) -> &mut Self;
+
+    /// Save resource `R`. On rollback, `R` is restored, inserted or removed to match the
+    /// snapshot.
+    fn add_rollback_resource<R: Resource + Clone>(&mut self) -> &mut Self;
+
+    /// Save resource `R` as input. While re-simulating, `R` is restored at the start of every
+    /// tick, so each tick sees the same input it saw originally. Once re-simulation finishes, the
+    /// live value of `R` is put back.
+    ///
+    /// Inputs can be corrected before rolling back with [`Snapshots::input_mut`].
+    fn add_rollback_input<R: Resource + Clone>(&mut self) -> &mut Self;
+}
+
+impl AddRollback for App {
+    fn add_rollback_component<C: Component + Clone>(&mut self) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(Snapshots::default)
+            .register(SnapshotKind::Component, SnapshotChannel::component::<C>());
+        self
+    }
+
+    fn add_rollback_resource<R: Resource + Clone>(&mut self) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(Snapshots::default)
+            .register(SnapshotKind::Resource, SnapshotChannel::resource::<R>());
+        self
+    }
+
+    fn add_rollback_input<R: Resource + Clone>(&mut self) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(Snapshots::default)
+            .register(SnapshotKind::Input, SnapshotChannel::resource::<R>());
+        self
+    }
+}
+
+/// A marker component for entities which are saved by [`Snapshots`].
+///
+/// Entities spawned after a snapshot are despawned when rolling back to it, and entities
+/// despawned after a snapshot are spawned again with the saved components. Respawned entities
+/// receive a new [`Entity`], so components which store an [`Entity`] may need fixing up.
+#[derive(Clone, Component, Copy, Debug, Default)]
+pub struct Rollback;
+
+/// Saves the world at the start of every tick of [`CoreSchedule::FixedUpdate`], so it can be
+/// rolled back and re-simulated.
+///
+/// Snapshots are kept for [`Snapshots::capacity`] ticks. Only data registered with
+/// [`AddRollback`] is saved.
+///
+/// Events sent by fixed timestep systems are sent again while re-simulating, so that fixed
+/// timestep systems reading them behave the same as when the ticks first ran. Since systems
+/// outside of the fixed schedule already read these events, systems sending events meant for
+/// them (such as sounds or particles) should not send while [`Snapshots::is_resimulating`]:
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::prelude::*;
+/// pub struct PlaySound;
+///
+/// fn jump(mut sound_events: EventWriter<PlaySound>, snapshots: Res<Snapshots>) {
+///     // ...
+///     if !snapshots.is_resimulating() {
+///         sound_events.send(PlaySound);
+///     }
+/// }
+/// # bevy::ecs::system::assert_is_system(jump);
+/// ```
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::{fixed_timestep::InputLatch, prelude::*};
+/// fn correct_remote_input(mut snapshots: ResMut<Snapshots>) {
+///     // a late packet says the remote player pressed jump on tick 40
+///     if let Some(input) = snapshots.input_mut::<FixedInput<KeyCode>>(40) {
+///         input.latch(InputLatch::Press(KeyCode::Space));
+///         snapshots.rollback_to(40);
+///     }
+/// }
+/// # bevy::ecs::system::assert_is_system(correct_remote_input);
+/// ```
+#[derive(Resource)]
+pub struct Snapshots {
+    capacity: usize,
+    buffer: VecDeque<Snapshot>,
+    pending: Option<u64>,
+    resimulating: bool,
+    next_id: u64,
+    channels: SnapshotChannels,
+}
+
+impl Default for Snapshots {
+    fn default() -> Self {
+        Self {
+            capacity: 60,
+            buffer: VecDeque::new(),
+            pending: None,
+            resimulating: false,
+            next_id: 0,
+            channels: SnapshotChannels::default(),
+        }
+    }
+}
+
+impl Snapshots {
+    /// The number of ticks for which snapshots are kept.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Set the number of ticks for which snapshots are kept. Older snapshots are discarded
+    /// immediately.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.truncate();
+    }
+
+    /// The earliest tick which can be rolled back to.
+    #[must_use]
+    pub fn oldest_tick(&self) -> Option<u64> {
+        self.buffer.front().map(|snapshot| snapshot.tick)
+    }
+
+    /// The latest tick which can be rolled back to.
+    #[must_use]
+    pub fn newest_tick(&self) -> Option<u64> {
+        self.buffer.back().map(|snapshot| snapshot.tick)
+    }
+
+    /// Restore the world to the start of `tick` and re-simulate up to the current tick, during
+    /// the next frame. Returns `false` if there is no snapshot of `tick`.
+    ///
+    /// If several rollbacks are requested in the same frame, the earliest tick is used.
+    pub fn rollback_to(&mut self, tick: u64) -> bool {
+        if self.find(tick).is_none() {
+            return false;
+        }
+        self.pending = Some(self.pending.map_or(tick, |pending| pending.min(tick)));
+        true
+    }
+
+    /// The tick which will be rolled back to during the next frame.
+    #[must_use]
+    pub fn pending_rollback(&self) -> Option<u64> {
+        self.pending
+    }
+
+    /// Returns `true` while ticks are being re-simulated after a rollback.
+    #[must_use]
+    pub fn is_resimulating(&self) -> bool {
+        self.resimulating
+    }
+
+    /// The input `R` seen by `tick`, or [`None`] if there is no snapshot of `tick` or `R` was not
+    /// registered with [`AddRollback::add_rollback_input`].
+    #[must_use]
+    pub fn input<R: Resource>(&self, tick: u64) -> Option<&R> {
+        let channel = self.channel::<R>()?;
+        self.buffer[self.find(tick)?].inputs[channel]
+            .downcast_ref::<Option<R>>()?
+            .as_ref()
+    }
+
+    /// Mutable access to the input `R` seen by `tick`. Changes take effect when the world is
+    /// rolled back to `tick` or earlier.
+    #[must_use]
+    pub fn input_mut<R: Resource>(&mut self, tick: u64) -> Option<&mut R> {
+        let channel = self.channel::<R>()?;
+        let index = self.find(tick)?;
+        self.buffer[index].inputs[channel]
+            .downcast_mut::<Option<R>>()?
+            .as_mut()
+    }
+
+    /// Discard all snapshots.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.pending = None;
+    }
+
+    fn register(&mut self, kind: SnapshotKind, channel: SnapshotChannel) {
+        let channels = match kind {
+            SnapshotKind::Component => &mut self.channels.components,
+            SnapshotKind::Resource => &mut self.channels.resources,
+            SnapshotKind::Input => &mut self.channels.inputs,
+        };
+        if channels
+            .iter()
+            .any(|registered| registered.type_id == channel.type_id)
+        {
+            return;
+        }
+        channels.push(channel);
+        // older snapshots are missing the new channel
+        self.clear();
+    }
+
+    fn find(&self, tick: u64) -> Option<usize> {
+        let oldest = self.oldest_tick()?;
+        let index = usize::try_from(tick.checked_sub(oldest)?).ok()?;
+        (self.buffer.get(index)?.tick == tick).then_some(index)
+    }
+
+    fn channel<R: Resource>(&self) -> Option<usize> {
+        self.channels
+            .inputs
+            .iter()
+            .position(|channel| channel.type_id == TypeId::of::<R>())
+    }
+
+    fn truncate(&mut self) {
+        while self.buffer.len() > self.capacity {
+            self.buffer.pop_front();
+        }
+    }
+}
+
+enum SnapshotKind {
+    Component,
+    Resource,
+    Input,
+}
+
+type SnapshotData = Box<dyn Any + Send + Sync>;
+
+#[derive(Clone, Default)]
+struct SnapshotChannels {
+    components: Vec<SnapshotChannel>,
+    resources: Vec<SnapshotChannel>,
+    inputs: Vec<SnapshotChannel>,
+}
+
+#[derive(Clone, Copy)]
+struct SnapshotChannel {
+    type_id: TypeId,
+    save: fn(&mut World) -> SnapshotData,
+    load: fn(&mut World, &SnapshotData, &HashMap<RollbackId, Entity>),
+}
+
+impl SnapshotChannel {
+    fn component<C: Component + Clone>() -> Self {
+        Self {
+            type_id: TypeId::of::<C>(),
+            save: save_component::<C>,
+            load: load_component::<C>,
+        }
+    }
+
+    fn resource<R: Resource + Clone>() -> Self {
+        Self {
+            type_id: TypeId::of::<R>(),
+            save: save_resource::<R>,
+            load: load_resource::<R>,
+        }
+    }
+}
+
+struct Snapshot {
+    tick: u64,
+    entities: Vec<RollbackId>,
+    components: Vec<SnapshotData>,
+    resources: Vec<SnapshotData>,
+    inputs: Vec<SnapshotData>,
+}
+
+/// Identifies a [`Rollback`] entity across despawns and rollbacks, unlike [`Entity`]. Assigned
+/// when the entity is first saved by [`Snapshots`], at the start of the next tick.
+///
+/// Useful for keying per-entity [`FixedRng::stream`]s.
+#[derive(Clone, Component, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RollbackId(u64);
+
+impl RollbackId {
+    /// The id as a number.
+    #[must_use]
+    pub fn get(&self) -> u64 {
+        self.0
+    }
+}
+
+fn save_component<C: Component + Clone>(world: &mut World) -> SnapshotData {
+    let mut query = world.query::<(&RollbackId, &C)>();
+    let saved: Vec<(RollbackId, C)> = query
+        .iter(world)
+        .map(|(id, component)| (*id, component.clone()))
+        .collect();
+    Box::new(saved)
+}
+
+fn load_component<C: Component + Clone>(
+    world: &mut World,
+    saved: &SnapshotData,
+    entities: &HashMap<RollbackId, Entity>,
+) {
+    let Some(saved) = saved.downcast_ref::<Vec<(RollbackId, C)>>() else {
+        return;
+    };
+    let saved_ids: HashSet<RollbackId> = saved.iter().map(|(id, _)| *id).collect();
+    let mut query = world.query_filtered::<(Entity, &RollbackId), With<C>>();
+    let stale: Vec<Entity> = query
+        .iter(world)
+        .filter(|(_, id)| !saved_ids.contains(id))
+        .map(|(entity, _)| entity)
+        .collect();
+    for entity in stale {
+        world.entity_mut(entity).remove::<C>();
+    }
+    for (id, component) in saved {
+        if let Some(&entity) = entities.get(id) {
+            world.entity_mut(entity).insert(component.clone());
+        }
+    }
+}
+
+fn save_resource<R: Resource + Clone>(world: &mut World) -> SnapshotData {
+    Box::new(world.get_resource::<R>().cloned())
+}
+
+fn load_resource<R: Resource + Clone>(
+    world: &mut World,
+    saved: &SnapshotData,
+    _entities: &HashMap<RollbackId, Entity>,
+) {
+    match saved.downcast_ref::<Option<R>>() {
+        Some(Some(resource)) => world.insert_resource(resource.clone()),
+        Some(None) => {
+            world.remove_resource::<R>();
+        }
+        None => {}
+    }
+}
+
+/// Despawn entities which did not exist when `ids` were saved and respawn those which have been
+/// despawned since, returning the entity for each id.
+fn load_entities(world: &mut World, ids: &[RollbackId]) -> HashMap<RollbackId, Entity> {
+    let mut query = world.query::<(Entity, &RollbackId)>();
+    let mut entities: HashMap<RollbackId, Entity> = query
+        .iter(world)
+        .map(|(entity, id)| (*id, entity))
+        .collect();
+    let saved_ids: HashSet<RollbackId> = ids.iter().copied().collect();
+    for (id, entity) in &entities {
+        if !saved_ids.contains(id) && world.get_entity(*entity).is_some() {
+            despawn_with_children_recursive(world, *entity);
+        }
+    }
+    entities.retain(|id, entity| saved_ids.contains(id) && world.get_entity(*entity).is_some());
+    for id in ids {
+        if !entities.contains_key(id) {
+            entities.insert(*id, world.spawn((Rollback, *id)).id());
+        }
+    }
+    entities
+}
+
+fn save_channels(world: &mut World, channels: &[SnapshotChannel]) -> Vec<SnapshotData> {
+    channels
+        .iter()
+        .map(|channel| (channel.save)(world))
+        .collect()
+}
+
+fn load_channels(
+    world: &mut World,
+    channels: &[SnapshotChannel],
+    saved: &[SnapshotData],
+    entities: &HashMap<RollbackId, Entity>,
+) {
+    for (channel, saved) in channels.iter().zip(saved) {
+        (channel.load)(world, saved, entities);
+    }
+}
+
+fn rollback_snapshot(world: &mut World) {
+    let snapshots = world.resource::<Snapshots>();
+    if snapshots.capacity == 0 {
+        return;
+    }
+    let channels = snapshots.channels.clone();
+    let mut next_id = snapshots.next_id;
+    let mut query = world.query_filtered::<Entity, (With<Rollback>, Without<RollbackId>)>();
+    let unassigned: Vec<Entity> = query.iter(world).collect();
+    for entity in unassigned {
+        world.entity_mut(entity).insert(RollbackId(next_id));
+        next_id += 1;
+    }
+    let mut query = world.query::<&RollbackId>();
+    let snapshot = Snapshot {
+        tick: world.resource::<FixedTick>().0 + 1,
+        entities: query.iter(world).copied().collect(),
+        components: save_channels(world, &channels.components),
+        resources: save_channels(world, &channels.resources),
+        inputs: save_channels(world, &channels.inputs),
+    };
+    let mut snapshots = world.resource_mut::<Snapshots>();
+    snapshots.next_id = next_id;
+    // re-simulated ticks replace their old snapshots
+    while snapshots
+        .newest_tick()
+        .is_some_and(|newest| newest >= snapshot.tick)
+    {
+        snapshots.buffer.pop_back();
+    }
+    snapshots.buffer.push_back(snapshot);
+    snapshots.truncate();
+}
+
+fn rollback_drive(world: &mut World) {
+    if is_fixed_update_suspended(world) {
+        return;
+    }
+    let mut snapshots = world.resource_mut::<Snapshots>();
+    let Some(tick) = snapshots.pending.take() else {
+        return;
+    };
+    let Some(index) = snapshots.find(tick) else {
+        return;
+    };
+    let resimulate: Vec<Snapshot> = snapshots.buffer.drain(index..).collect();
+    let channels = snapshots.channels.clone();
+    let live_inputs = save_channels(world, &channels.inputs);
+    let snapshot = &resimulate[0];
+    let entities = load_entities(world, &snapshot.entities);
+    load_channels(world, &channels.components, &snapshot.components, &entities);
+    load_channels(world, &channels.resources, &snapshot.resources, &entities);
+    world.resource_mut::<Snapshots>().resimulating = true;
+    for snapshot in &resimulate {
+        load_channels(world, &channels.inputs, &snapshot.inputs, &entities);
+        world.run_schedule(CoreSchedule::FixedUpdate);
+    }
+    world.resource_mut::<Snapshots>().resimulating = false;
+    load_channels(world, &channels.inputs, &live_inputs, &entities);
+}
@@ -0,0 +1,8 @@
+//! Helpers for testing gameplay built on Halia without a window or real time.
+//!
+//! - [`HaliaTestApp`]
+//!
+//! Feature flag: `halia_test`
+
+mod test_app;
+pub use test_app::*;
@@ -0,0 +1,219 @@
+use std::{
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
+
+use bevy::{
+    input::{keyboard::KeyboardInput, mouse::MouseButtonInput, ButtonState, InputPlugin},
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::Instant,
+};
+
+use crate::fixed_timestep::FixedSet;
+
+/// A headless [`App`] for testing fixed timestep gameplay, where time only passes when ticks are
+/// requested.
+///
+/// Built with [`MinimalPlugins`], the input, transform and hierarchy plugins, and the given Halia
+/// plugins. Dereferences to [`App`], so systems and resources can be added and the world can be
+/// inspected directly.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use halia::prelude::*;
+/// # use halia::testing::HaliaTestApp;
+/// #[derive(Component)]
+/// struct Player;
+///
+/// fn move_player(
+///     mut player_query: Query<&mut Transform2, With<Player>>,
+///     input: Res<FixedInput<KeyCode>>,
+/// ) {
+///     for mut transform in player_query.iter_mut() {
+///         if input.pressed(KeyCode::D) {
+///             transform.translation.x += 1.;
+///         }
+///     }
+/// }
+///
+/// let mut app = HaliaTestApp::new(HaliaPlugins);
+/// app.add_system(move_player.in_schedule(CoreSchedule::FixedUpdate));
+/// let player = app
+///     .world
+///     .spawn((Player, Transform2::default(), TransformBundle::default()))
+///     .id();
+///
+/// app.press(KeyCode::D);
+/// app.tick(3);
+/// assert_eq!(app.world.get::<Transform2>(player).unwrap().translation.x, 3.);
+/// ```
+pub struct HaliaTestApp {
+    app: App,
+    instant: Instant,
+}
+
+impl HaliaTestApp {
+    /// The number of frames in a row [`HaliaTestApp::tick`] runs without any ticks before giving
+    /// up.
+    pub const MAX_IDLE_FRAMES: u32 = 1000;
+
+    /// Build an app with `plugins`, and run its first frame, which runs no ticks.
+    #[must_use]
+    pub fn new(plugins: impl PluginGroup) -> Self {
+        let instant = Instant::now();
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(InputPlugin)
+            .add_plugin(TransformPlugin)
+            .add_plugin(HierarchyPlugin)
+            .add_plugins(plugins)
+            .init_resource::<TestTicks>()
+            .add_system(
+                test_ticks_update
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_base_set(FixedSet::Last),
+            )
+            .insert_resource(TimeUpdateStrategy::ManualInstant(instant));
+        app.update();
+        Self { app, instant }
+    }
+
+    /// Run frames, advancing time by [`FixedTime::period`] each frame, until at least `ticks`
+    /// ticks of [`CoreSchedule::FixedUpdate`] have run. Ticks re-simulated by rollback are not
+    /// counted.
+    ///
+    /// Usually one tick runs per frame, but
+    /// [`FixedTimeControl`](`crate::fixed_timestep::FixedTimeControl`) can change that.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`HaliaTestApp::MAX_IDLE_FRAMES`] frames in a row run no ticks, such as while the
+    /// fixed schedule is paused.
+    pub fn tick(&mut self, ticks: u32) -> &mut Self {
+        let target = self.ticks() + u64::from(ticks);
+        let mut idle_frames = 0;
+        while self.ticks() < target {
+            let ticks = self.ticks();
+            self.frames(1);
+            if self.ticks() == ticks {
+                idle_frames += 1;
+                assert!(
+                    idle_frames < Self::MAX_IDLE_FRAMES,
+                    "no ticks ran for {idle_frames} frames"
+                );
+            } else {
+                idle_frames = 0;
+            }
+        }
+        self
+    }
+
+    /// Run `frames` frames, advancing time by [`FixedTime::period`] each frame.
+    pub fn frames(&mut self, frames: u32) -> &mut Self {
+        for _ in 0..frames {
+            let period = self.app.world.resource::<FixedTime>().period;
+            self.advance(period);
+        }
+        self
+    }
+
+    /// Run a frame which advances time by `duration`.
+    pub fn advance(&mut self, duration: Duration) -> &mut Self {
+        self.instant += duration;
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualInstant(self.instant));
+        self.app.update();
+        self
+    }
+
+    /// Run a frame without advancing time, so no ticks run.
+    pub fn frame(&mut self) -> &mut Self {
+        self.app.update();
+        self
+    }
+
+    /// Press a key, which is seen by the next tick.
+    pub fn press(&mut self, key_code: KeyCode) -> &mut Self {
+        self.send_key(key_code, ButtonState::Pressed)
+    }
+
+    /// Release a key, which is seen by the next tick.
+    pub fn release(&mut self, key_code: KeyCode) -> &mut Self {
+        self.send_key(key_code, ButtonState::Released)
+    }
+
+    /// Press a mouse button, which is seen by the next tick.
+    pub fn press_mouse(&mut self, button: MouseButton) -> &mut Self {
+        self.send_mouse(button, ButtonState::Pressed)
+    }
+
+    /// Release a mouse button, which is seen by the next tick.
+    pub fn release_mouse(&mut self, button: MouseButton) -> &mut Self {
+        self.send_mouse(button, ButtonState::Released)
+    }
+
+    /// Move the cursor to `position`. Without a window or camera, this sets both
+    /// [`Cursor::window_position`](`crate::cursor::Cursor::window_position`) and
+    /// [`Cursor::world_position`](`crate::cursor::Cursor::world_position`).
+    #[cfg(feature = "halia_cursor")]
+    pub fn move_cursor(&mut self, position: Vec2) -> &mut Self {
+        let mut cursor = self
+            .app
+            .world
+            .get_resource_or_insert_with(crate::cursor::Cursor::default);
+        cursor.window_position = position;
+        cursor.world_position = position;
+        self
+    }
+
+    fn ticks(&self) -> u64 {
+        self.app.world.resource::<TestTicks>().0
+    }
+
+    fn send_key(&mut self, key_code: KeyCode, state: ButtonState) -> &mut Self {
+        self.app.world.send_event(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key_code),
+            state,
+        });
+        self
+    }
+
+    fn send_mouse(&mut self, button: MouseButton, state: ButtonState) -> &mut Self {
+        self.app
+            .world
+            .send_event(MouseButtonInput { button, state });
+        self
+    }
+}
+
+impl Deref for HaliaTestApp {
+    type Target = App;
+
+    fn deref(&self) -> &Self::Target {
+        &self.app
+    }
+}
+
+impl DerefMut for HaliaTestApp {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.app
+    }
+}
+
+/// The number of ticks of [`CoreSchedule::FixedUpdate`] which have run, not counting ticks
+/// re-simulated by rollback.
+#[derive(Default, Resource)]
+struct TestTicks(u64);
+
+fn test_ticks_update(
+    mut test_ticks: ResMut<TestTicks>,
+    #[cfg(feature = "halia_rollback")] snapshots: Option<Res<crate::rollback::Snapshots>>,
+) {
+    #[cfg(feature = "halia_rollback")]
+    if snapshots.is_some_and(|snapshots| snapshots.is_resimulating()) {
+        return;
+    }
+    test_ticks.0 += 1;
+}
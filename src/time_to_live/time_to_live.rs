@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use bevy::prelude::*;
 
-use crate::fixed_timestep::FixedSet;
+use crate::fixed_timestep::{FixedDuration, FixedSet, FixedTimer};
 
 /// Time to live system set.
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
@@ -30,18 +30,31 @@ impl Plugin for TimeToLivePlugin {
 
 /// A component which will recursively despawn the entity its attached to after a specified amount
 /// of time.
-#[derive(Component, Debug, Clone, Copy)]
+///
+/// The time is counted in whole fixed ticks, so the entity is despawned on the same tick every
+/// time.
+#[derive(Component, Debug, Clone)]
 pub struct TimeToLive {
-    /// The amount of time left before the entity is despawned.
-    pub time_remaining: f32,
+    /// The timer which despawns the entity when it finishes.
+    pub timer: FixedTimer,
 }
 
 impl TimeToLive {
     /// Instantiate a new [`TimeToLive`] component with time remaining set to `duration`.
     #[must_use]
     pub fn new(duration: Duration) -> Self {
+        Self::from_fixed_duration(FixedDuration::Time(duration))
+    }
+
+    /// Instantiate a new [`TimeToLive`] component which lasts an exact number of ticks.
+    #[must_use]
+    pub fn from_ticks(ticks: u64) -> Self {
+        Self::from_fixed_duration(FixedDuration::Ticks(ticks))
+    }
+
+    fn from_fixed_duration(duration: FixedDuration) -> Self {
         Self {
-            time_remaining: duration.as_secs_f32(),
+            timer: FixedTimer::new(duration, TimerMode::Once),
         }
     }
 }
@@ -52,11 +65,10 @@ fn time_to_live_update(
     time: Res<FixedTime>,
 ) {
     for (time_to_live_entity, mut time_to_live) in time_to_live_query.iter_mut() {
-        if time_to_live.time_remaining <= 0. {
+        if time_to_live.timer.tick(time.period).finished() {
             if let Some(entity_commands) = commands.get_entity(time_to_live_entity) {
                 entity_commands.despawn_recursive();
             }
         }
-        time_to_live.time_remaining -= time.period.as_secs_f32();
     }
 }
@@ -114,6 +114,20 @@ impl Transform2 {
         self.scale = scale;
         self
     }
+
+    /// Linearly interpolates between this [`Transform2`] and `other` by `t`. Rotation takes the
+    /// shortest path.
+    #[must_use]
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        let rotation_delta = (other.rotation - self.rotation + std::f32::consts::PI)
+            .rem_euclid(std::f32::consts::TAU)
+            - std::f32::consts::PI;
+        Transform2 {
+            translation: self.translation.lerp(other.translation, t),
+            rotation: self.rotation + rotation_delta * t,
+            scale: self.scale.lerp(other.scale, t),
+        }
+    }
 }
 
 impl Default for Transform2 {
@@ -158,19 +172,7 @@ fn update_transform2_recursive(
     mut cumulative_depth: f32,
 ) {
     if let Ok((mut transform, transform2, depth)) = transform_query.get_mut(entity) {
-        if let Some(transform2) = transform2 {
-            transform.translation.x = transform2.translation.x;
-            transform.translation.y = transform2.translation.y;
-            transform.scale = transform2.scale.extend(1.);
-            transform.rotation = Quat::from_rotation_z(transform2.rotation);
-        }
-        if let Some(depth) = depth {
-            transform.translation.z = match depth {
-                Depth::Exact(depth_value) => *depth_value - cumulative_depth,
-                Depth::Inherit(depth_value) => *depth_value,
-            };
-        }
-        cumulative_depth += transform.translation.z;
+        cumulative_depth = apply_transform2(&mut transform, transform2, depth, cumulative_depth);
     }
     if let Ok(children) = children_query.get(entity) {
         for child in children.iter() {
@@ -178,3 +180,28 @@ fn update_transform2_recursive(
         }
     }
 }
+
+/// Apply [`Transform2`] and [`Depth`] to `transform`, only marking it as changed if its value
+/// changes. Returns the cumulative depth to pass to children.
+pub(crate) fn apply_transform2(
+    transform: &mut Mut<Transform>,
+    transform2: Option<&Transform2>,
+    depth: Option<&Depth>,
+    cumulative_depth: f32,
+) -> f32 {
+    let mut new_transform = **transform;
+    if let Some(transform2) = transform2 {
+        new_transform.translation.x = transform2.translation.x;
+        new_transform.translation.y = transform2.translation.y;
+        new_transform.scale = transform2.scale.extend(1.);
+        new_transform.rotation = Quat::from_rotation_z(transform2.rotation);
+    }
+    if let Some(depth) = depth {
+        new_transform.translation.z = match depth {
+            Depth::Exact(depth_value) => *depth_value - cumulative_depth,
+            Depth::Inherit(depth_value) => *depth_value,
+        };
+    }
+    transform.set_if_neq(new_transform);
+    cumulative_depth + new_transform.translation.z
+}
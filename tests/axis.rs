@@ -0,0 +1,72 @@
+#![cfg(feature = "halia_test")]
+
+use bevy::{input::mouse::MouseMotion, prelude::*};
+use halia::{prelude::*, testing::HaliaTestApp};
+
+const STICK: GamepadAxis = GamepadAxis {
+    gamepad: Gamepad { id: 0 },
+    axis_type: GamepadAxisType::LeftStickX,
+};
+
+#[derive(Default, Resource)]
+struct Log(Vec<(f32, f32)>);
+
+fn log(mut log: ResMut<Log>, stick: Res<FixedAxis<GamepadAxis>>, mouse: Res<FixedAxis<MouseAxis>>) {
+    log.0
+        .push((stick.get(STICK), mouse.get(MouseAxis::MotionX)));
+}
+
+fn app() -> HaliaTestApp {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.init_resource::<Log>()
+        .add_system(log.in_schedule(CoreSchedule::FixedUpdate));
+    app
+}
+
+fn move_mouse(app: &mut HaliaTestApp, x: f32) {
+    app.world.send_event(MouseMotion {
+        delta: Vec2::new(x, 0.),
+    });
+}
+
+fn set_stick(app: &mut HaliaTestApp, value: f32) {
+    app.world
+        .resource_mut::<Axis<GamepadAxis>>()
+        .set(STICK, value);
+}
+
+#[test]
+fn mouse_motion_is_summed_between_ticks() {
+    let mut app = app();
+    move_mouse(&mut app, 2.);
+    app.frame();
+    move_mouse(&mut app, 3.);
+    app.tick(1);
+    app.tick(1);
+    assert_eq!(app.world.resource::<Log>().0, vec![(0., 5.), (0., 0.)]);
+}
+
+#[test]
+fn sticks_keep_their_latest_value_outside_the_dead_zone() {
+    let mut app = app();
+    app.world
+        .resource_mut::<FixedAxis<GamepadAxis>>()
+        .set_default_dead_zone(0.2);
+    set_stick(&mut app, 0.9);
+    app.frame();
+    set_stick(&mut app, 0.5);
+    app.tick(1);
+    app.tick(1);
+    set_stick(&mut app, -0.1);
+    app.tick(1);
+    assert_eq!(
+        app.world.resource::<Log>().0,
+        vec![(0.5, 0.), (0.5, 0.), (0., 0.)]
+    );
+    assert_eq!(
+        app.world
+            .resource::<FixedAxis<GamepadAxis>>()
+            .get_raw(STICK),
+        -0.1
+    );
+}
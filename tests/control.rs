@@ -0,0 +1,114 @@
+#![cfg(feature = "halia_test")]
+
+use std::time::Duration;
+
+use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
+use halia::{fixed_timestep::FixedTimestepOverrun, prelude::*, testing::HaliaTestApp};
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, ScheduleLabel)]
+struct SlowUpdate;
+
+#[derive(Default, Resource)]
+struct Frames(u32);
+
+#[derive(Default, Resource)]
+struct Jumps(Vec<u64>);
+
+fn count_frames(mut frames: ResMut<Frames>) {
+    frames.0 += 1;
+}
+
+fn jump(mut jumps: ResMut<Jumps>, keys: Res<FixedInput<KeyCode>>, fixed_tick: Res<FixedTick>) {
+    if keys.just_pressed(KeyCode::Space) {
+        jumps.0.push(fixed_tick.0);
+    }
+}
+
+#[derive(Default, Resource)]
+struct Overruns(Vec<(String, u32, u64)>);
+
+fn collect_overruns(
+    mut overruns: ResMut<Overruns>,
+    mut overrun_events: EventReader<FixedTimestepOverrun>,
+) {
+    for overrun in overrun_events.iter() {
+        overruns.0.push((
+            format!("{:?}", overrun.schedule),
+            overrun.ticks,
+            overrun.dropped,
+        ));
+    }
+}
+
+fn overrun_app() -> HaliaTestApp {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.insert_resource(FixedTime::new(Duration::from_millis(100)))
+        .insert_resource(FixedTimeControl::default().with_max_ticks_per_frame(Some(2)))
+        .add_fixed_schedule(SlowUpdate, Duration::from_millis(250))
+        .init_resource::<Overruns>()
+        .add_system(collect_overruns);
+    app
+}
+
+fn overruns_after_a_second(app: &mut HaliaTestApp) -> Vec<(String, u32, u64)> {
+    app.advance(Duration::from_secs(1));
+    std::mem::take(&mut app.world.resource_mut::<Overruns>().0)
+}
+
+#[test]
+fn every_catch_up_policy_reports_dropped_ticks() {
+    let mut app = overrun_app();
+    let dropped = |fixed_update, slow_update| {
+        vec![
+            ("FixedUpdate".to_owned(), 2, fixed_update),
+            ("SlowUpdate".to_owned(), 2, slow_update),
+        ]
+    };
+
+    assert_eq!(overruns_after_a_second(&mut app), dropped(8, 2));
+
+    app.world
+        .resource_mut::<FixedTimeControl>()
+        .set_catch_up(CatchUp::Drop);
+    assert_eq!(overruns_after_a_second(&mut app), dropped(8, 2));
+
+    app.world
+        .resource_mut::<FixedTimeControl>()
+        .set_catch_up(CatchUp::CarryOver);
+    assert_eq!(overruns_after_a_second(&mut app), vec![]);
+}
+
+#[test]
+fn pause_step_and_scale_control_ticks() {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.insert_resource(FixedTime::new(Duration::from_millis(100)))
+        .init_resource::<Frames>()
+        .init_resource::<Jumps>()
+        .add_system(count_frames)
+        .add_system(jump.in_schedule(CoreSchedule::FixedUpdate));
+    let fixed_tick = |app: &HaliaTestApp| app.world.resource::<FixedTick>().0;
+
+    app.world.resource_mut::<FixedTimeControl>().set_scale(0.5);
+    app.advance(Duration::from_millis(300));
+    assert_eq!(fixed_tick(&app), 1);
+
+    // frames keep running and presses are kept while paused
+    app.world.resource_mut::<FixedTimeControl>().pause();
+    app.press(KeyCode::Space);
+    let frames = app.world.resource::<Frames>().0;
+    app.advance(Duration::from_secs(1))
+        .advance(Duration::from_secs(1));
+    assert_eq!(fixed_tick(&app), 1);
+    assert_eq!(app.world.resource::<Frames>().0, frames + 2);
+
+    app.world.resource_mut::<FixedTimeControl>().step(2);
+    app.frame();
+    assert_eq!(fixed_tick(&app), 3);
+    app.frame();
+    assert_eq!(fixed_tick(&app), 3);
+    assert_eq!(app.world.resource::<Jumps>().0, vec![2]);
+
+    app.world.resource_mut::<FixedTimeControl>().resume();
+    app.advance(Duration::from_millis(400));
+    assert_eq!(fixed_tick(&app), 5);
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "halia_test")]
+
+use std::time::Duration;
+
+use bevy::{
+    diagnostic::{DiagnosticId, Diagnostics},
+    ecs::schedule::ScheduleLabel,
+    prelude::*,
+};
+use halia::{fixed_timestep::FixedTimestepDiagnostics, prelude::*, testing::HaliaTestApp};
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, ScheduleLabel)]
+struct FastUpdate;
+
+fn measurements(app: &HaliaTestApp, id: DiagnosticId) -> Vec<f64> {
+    app.world
+        .resource::<Diagnostics>()
+        .get(id)
+        .unwrap()
+        .values()
+        .copied()
+        .collect()
+}
+
+fn set_durations(diagnostics: &FixedTimestepDiagnostics) -> [Duration; 6] {
+    [
+        diagnostics.first(),
+        diagnostics.pre_update(),
+        diagnostics.state_transitions(),
+        diagnostics.update(),
+        diagnostics.post_update(),
+        diagnostics.last(),
+    ]
+}
+
+#[test]
+fn measures_each_tick_of_a_frame() {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.world.resource_mut::<FixedTimeControl>().pause();
+    app.world.resource_mut::<FixedTimeControl>().step(3);
+    app.frames(1);
+
+    let fixed_timestep_diagnostics = app.world.resource::<FixedTimestepDiagnostics>();
+    assert_eq!(fixed_timestep_diagnostics.ticks(), 3);
+    assert!(fixed_timestep_diagnostics.worst_tick() >= fixed_timestep_diagnostics.average_tick());
+    for set in set_durations(fixed_timestep_diagnostics) {
+        assert!(set <= fixed_timestep_diagnostics.average_tick());
+    }
+    assert_eq!(
+        measurements(&app, FixedTimestepDiagnostics::TICKS_PER_FRAME).last(),
+        Some(&3.)
+    );
+    assert_eq!(
+        measurements(&app, FixedTimestepDiagnostics::UPDATE_DURATION).len(),
+        1
+    );
+}
+
+#[test]
+fn frames_without_ticks_keep_durations() {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.tick(1);
+    let measured = app.world.resource::<FixedTimestepDiagnostics>().clone();
+    let updates = measurements(&app, FixedTimestepDiagnostics::UPDATE_DURATION);
+
+    app.world.resource_mut::<FixedTimeControl>().pause();
+    app.frames(1);
+
+    let fixed_timestep_diagnostics = app.world.resource::<FixedTimestepDiagnostics>();
+    assert_eq!(fixed_timestep_diagnostics.ticks(), 0);
+    assert_eq!(
+        fixed_timestep_diagnostics.average_tick(),
+        measured.average_tick()
+    );
+    assert_eq!(
+        set_durations(fixed_timestep_diagnostics),
+        set_durations(&measured)
+    );
+    assert_eq!(
+        measurements(&app, FixedTimestepDiagnostics::TICKS_PER_FRAME).last(),
+        Some(&0.)
+    );
+    assert_eq!(
+        measurements(&app, FixedTimestepDiagnostics::UPDATE_DURATION),
+        updates
+    );
+}
+
+#[test]
+fn only_fixed_update_ticks_are_measured() {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.insert_resource(FixedTime::new(Duration::from_millis(100)))
+        .add_fixed_schedule(FastUpdate, Duration::from_millis(25));
+    app.advance(Duration::from_millis(100));
+
+    assert_eq!(app.world.resource::<FixedTimestepDiagnostics>().ticks(), 1);
+}
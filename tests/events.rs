@@ -0,0 +1,118 @@
+#![cfg(feature = "halia_test")]
+
+use bevy::prelude::*;
+use halia::{prelude::*, testing::HaliaTestApp};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Ping(u32);
+
+#[derive(Default, Resource)]
+struct Received(Vec<(u64, u32)>);
+
+#[derive(Default, Resource)]
+struct Within(Vec<(u64, u32)>);
+
+#[derive(Default, Resource)]
+struct Echo(Option<u32>);
+
+fn receive(
+    mut received: ResMut<Received>,
+    mut ping_events: EventReader<Ping>,
+    fixed_tick: Res<FixedTick>,
+) {
+    for ping in ping_events.iter() {
+        received.0.push((fixed_tick.0, ping.0));
+    }
+}
+
+fn echo(mut echo: ResMut<Echo>, mut ping_events: EventWriter<Ping>) {
+    if let Some(ping) = echo.0.take() {
+        ping_events.send(Ping(ping));
+    }
+}
+
+fn receive_within(
+    mut within: ResMut<Within>,
+    mut ping_events: FixedEventReader<Ping>,
+    fixed_tick: Res<FixedTick>,
+) {
+    for ping in ping_events.iter_within(2) {
+        within.0.push((fixed_tick.0, ping.0));
+    }
+}
+
+fn send_ticked(mut ping_events: FixedEventWriter<Ping>, fixed_tick: Res<FixedTick>) {
+    if let Ok(ping) = u32::try_from(fixed_tick.0) {
+        ping_events.send(Ping(ping));
+    }
+}
+
+/// Stop reading during ticks 4 and 5, so pings sent meanwhile are held over.
+fn listening(fixed_tick: Res<FixedTick>) -> bool {
+    !(4..6).contains(&fixed_tick.0)
+}
+
+fn app() -> HaliaTestApp {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.add_fixed_event::<Ping>()
+        .init_resource::<Received>()
+        .init_resource::<Echo>()
+        .add_systems((receive, echo.after(receive)).in_schedule(CoreSchedule::FixedUpdate));
+    app
+}
+
+#[test]
+fn events_are_read_once_when_frames_run_no_ticks() {
+    let mut app = app();
+    app.world.send_event(Ping(1));
+    app.frame().frame().tick(1);
+    app.tick(2);
+    assert_eq!(app.world.resource::<Received>().0, vec![(1, 1)]);
+}
+
+#[test]
+fn events_are_read_once_when_frames_run_several_ticks() {
+    let mut app = app();
+    app.world.send_event(Ping(1));
+    app.world.resource_mut::<FixedTimeControl>().step(2);
+    app.tick(1);
+    assert_eq!(app.world.resource::<FixedTick>().0, 3);
+    app.tick(1);
+    assert_eq!(app.world.resource::<Received>().0, vec![(1, 1)]);
+}
+
+#[test]
+fn events_sent_by_later_systems_are_read_next_tick() {
+    let mut app = app();
+    app.world.resource_mut::<Echo>().0 = Some(2);
+    app.tick(1).frame().tick(2);
+    assert_eq!(app.world.resource::<Received>().0, vec![(2, 2)]);
+}
+
+#[test]
+fn iter_within_includes_the_current_tick() {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.add_ticked_fixed_event::<Ping>()
+        .init_resource::<Within>()
+        .add_systems(
+            (
+                send_ticked,
+                receive_within.before(send_ticked).run_if(listening),
+            )
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    // each tick reads the previous tick's ping, which is within 2 ticks
+    app.tick(3);
+    assert_eq!(app.world.resource::<Within>().0, vec![(2, 1), (3, 2)]);
+
+    // run ticks 4 to 6 in one frame, so pings 3 and 4 are still unread at tick 6 but sent too
+    // long ago
+    app.world.resource_mut::<FixedTimeControl>().pause();
+    app.world.resource_mut::<FixedTimeControl>().step(3);
+    app.frames(1);
+    assert_eq!(app.world.resource::<FixedTick>().0, 6);
+    assert_eq!(
+        app.world.resource::<Within>().0,
+        vec![(2, 1), (3, 2), (6, 5)]
+    );
+}
@@ -0,0 +1,104 @@
+#![cfg(all(feature = "halia_test", feature = "halia_force_ratio"))]
+
+use bevy::{prelude::*, render::view::RenderLayers};
+use halia::{
+    force_ratio::{ForceRatioBar, ForceRatioBarCamera, ForceRatioLayers},
+    prelude::*,
+    testing::HaliaTestApp,
+};
+
+const PIXEL_PERFECT: ForceRatio = ForceRatio::PixelPerfect {
+    width: 160.,
+    height: 90.,
+};
+
+fn render_layers(app: &HaliaTestApp, entity: Entity) -> Option<Vec<u8>> {
+    app.world
+        .get::<RenderLayers>(entity)
+        .map(|layers| layers.iter().collect())
+}
+
+fn bars(app: &mut HaliaTestApp) -> Vec<(Option<Entity>, Vec<u8>)> {
+    let mut bar_query = app
+        .world
+        .query::<(&ForceRatioBar, Option<&ForceRatioBarCamera>, &RenderLayers)>();
+    let mut bars: Vec<(Option<Entity>, Vec<u8>)> = bar_query
+        .iter(&app.world)
+        .map(|(_, bar_camera, layers)| (bar_camera.map(|camera| camera.0), layers.iter().collect()))
+        .collect();
+    bars.sort();
+    bars.dedup();
+    bars
+}
+
+#[test]
+fn camera_bars_are_only_seen_by_their_camera() {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.insert_resource(ForceRatioLayers {
+        resource: 0,
+        cameras: 8..10,
+    });
+    let main_camera = app.world.spawn(Camera2dBundle::default()).id();
+    let minimap_camera = app
+        .world
+        .spawn((
+            Camera2dBundle::default(),
+            RenderLayers::layer(30),
+            ForceRatio::Disabled,
+        ))
+        .id();
+    let left_camera = app
+        .world
+        .spawn((
+            Camera2dBundle::default(),
+            RenderLayers::layer(30),
+            PIXEL_PERFECT,
+        ))
+        .id();
+    let right_camera = app
+        .world
+        .spawn((Camera2dBundle::default(), PIXEL_PERFECT))
+        .id();
+    app.frame().frame();
+
+    assert_eq!(render_layers(&app, main_camera), None);
+    assert_eq!(render_layers(&app, minimap_camera), Some(vec![30]));
+    assert_eq!(render_layers(&app, left_camera), Some(vec![8, 30]));
+    assert_eq!(render_layers(&app, right_camera), Some(vec![0, 9]));
+    assert_eq!(
+        bars(&mut app),
+        vec![
+            (None, vec![0]),
+            (Some(left_camera), vec![8]),
+            (Some(right_camera), vec![9]),
+        ]
+    );
+
+    app.world.entity_mut(left_camera).remove::<ForceRatio>();
+    app.world
+        .entity_mut(right_camera)
+        .insert(ForceRatio::Disabled);
+    app.frame().frame();
+
+    assert_eq!(render_layers(&app, left_camera), Some(vec![30]));
+    assert_eq!(render_layers(&app, right_camera), Some(vec![0]));
+    assert_eq!(bars(&mut app), vec![(None, vec![0])]);
+
+    app.world.entity_mut(minimap_camera).insert(PIXEL_PERFECT);
+    app.frame().frame();
+
+    assert_eq!(render_layers(&app, minimap_camera), Some(vec![8, 30]));
+    assert_eq!(
+        bars(&mut app),
+        vec![(None, vec![0]), (Some(minimap_camera), vec![8])]
+    );
+
+    app.world.resource_mut::<ForceRatioLayers>().resource = 5;
+    app.frame().frame();
+
+    assert_eq!(render_layers(&app, main_camera), None);
+    assert_eq!(
+        bars(&mut app),
+        vec![(None, vec![5]), (Some(minimap_camera), vec![8])]
+    );
+}
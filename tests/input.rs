@@ -0,0 +1,93 @@
+#![cfg(feature = "halia_test")]
+
+use bevy::prelude::*;
+use halia::{prelude::*, testing::HaliaTestApp};
+
+#[derive(Default, Resource)]
+struct Log(Vec<(Option<u32>, bool)>);
+
+fn log(mut log: ResMut<Log>, keys: Res<FixedInput<KeyCode>>) {
+    log.0.push((
+        keys.held_ticks(KeyCode::Space),
+        keys.just_double_tapped(KeyCode::Space, 3),
+    ));
+}
+
+#[derive(Default, Resource)]
+struct Jumps(Vec<u64>);
+
+fn jump(
+    mut jumps: ResMut<Jumps>,
+    mut keys: ResMut<FixedInput<KeyCode>>,
+    fixed_tick: Res<FixedTick>,
+) {
+    if keys.just_pressed_within(KeyCode::Space, 3) && keys.consume(KeyCode::Space) {
+        jumps.0.push(fixed_tick.0);
+    }
+}
+
+/// Land two ticks after the first tick, so a jump pressed during the first tick is buffered.
+fn landed(fixed_tick: Res<FixedTick>) -> bool {
+    fixed_tick.0 >= 3
+}
+
+fn app() -> HaliaTestApp {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.init_resource::<Log>()
+        .add_system(log.in_schedule(CoreSchedule::FixedUpdate));
+    app
+}
+
+#[test]
+fn held_ticks_counts_ticks_not_frames() {
+    let mut app = app();
+    app.press(KeyCode::Space).tick(1);
+    app.frame().frame().tick(2);
+    app.release(KeyCode::Space).tick(1);
+    assert_eq!(
+        app.world.resource::<Log>().0,
+        vec![
+            (Some(0), false),
+            (Some(1), false),
+            (Some(2), false),
+            (None, false)
+        ]
+    );
+    assert_eq!(
+        app.world
+            .resource::<FixedInput<KeyCode>>()
+            .released_after_ticks(KeyCode::Space),
+        Some(3)
+    );
+}
+
+#[test]
+fn double_taps_must_be_within_the_window() {
+    let mut app = app();
+    app.press(KeyCode::Space).tick(1);
+    app.release(KeyCode::Space).tick(1);
+    app.press(KeyCode::Space).tick(1);
+    app.release(KeyCode::Space).tick(3);
+    app.press(KeyCode::Space).tick(1);
+    let double_taps: Vec<bool> = app
+        .world
+        .resource::<Log>()
+        .0
+        .iter()
+        .map(|(_, double_tapped)| *double_tapped)
+        .collect();
+    assert_eq!(
+        double_taps,
+        vec![false, false, true, false, false, false, false]
+    );
+}
+
+#[test]
+fn buffered_presses_are_consumed_once() {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.init_resource::<Jumps>()
+        .add_system(jump.in_schedule(CoreSchedule::FixedUpdate).run_if(landed));
+    app.press(KeyCode::Space).tick(1);
+    app.release(KeyCode::Space).tick(4);
+    assert_eq!(app.world.resource::<Jumps>().0, vec![3]);
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "halia_test")]
+
+use bevy::prelude::*;
+use halia::{prelude::*, testing::HaliaTestApp};
+
+#[derive(Clone, Debug, Default, PartialEq, Resource)]
+struct WindowSize(f32);
+
+#[derive(Clone, Debug, Default, PartialEq, Resource)]
+struct Recoil(f32);
+
+#[derive(Default, Resource)]
+struct Log(Vec<(f32, f32)>);
+
+fn log(mut log: ResMut<Log>, window_size: Res<Fixed<WindowSize>>, recoil: Res<Fixed<Recoil>>) {
+    log.0.push((window_size.0 .0, recoil.0 .0));
+}
+
+/// Resize the window during a tick, which should only be seen by the next tick.
+fn resize(mut window_size: ResMut<WindowSize>) {
+    window_size.0 += 100.;
+}
+
+fn set(app: &mut HaliaTestApp, window_size: f32, recoil: f32) {
+    app.insert_resource(WindowSize(window_size))
+        .insert_resource(Recoil(recoil));
+}
+
+#[test]
+fn latched_resources_stay_the_same_for_a_tick() {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.insert_resource(WindowSize(100.))
+        .init_resource::<Recoil>()
+        .add_fixed_latch::<WindowSize>()
+        .add_fixed_latch_with::<Recoil>(|total, recoil| total.0 += recoil.0)
+        .init_resource::<Log>()
+        .add_systems((log, resize.after(log)).in_schedule(CoreSchedule::FixedUpdate));
+    set(&mut app, 200., 1.);
+    app.frame();
+    set(&mut app, 300., 2.);
+    app.frame();
+    set(&mut app, 400., 4.);
+    app.world.resource_mut::<FixedTimeControl>().step(2);
+    app.frame();
+
+    assert_eq!(app.world.resource::<Log>().0, vec![(400., 7.), (500., 0.)]);
+}
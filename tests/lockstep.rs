@@ -0,0 +1,131 @@
+#![cfg(all(feature = "halia_test", feature = "halia_lockstep"))]
+
+use bevy::prelude::*;
+use halia::{
+    lockstep::{LoopbackTransport, Transport},
+    prelude::*,
+    testing::HaliaTestApp,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+enum Action {
+    Jump,
+}
+
+#[derive(Default, Resource)]
+struct Jumps(Vec<(u64, usize)>);
+
+#[derive(Default, Resource)]
+struct Salt(u64);
+
+#[derive(Default, Resource)]
+struct Desyncs(Vec<LockstepDesync>);
+
+fn jump(
+    mut jumps: ResMut<Jumps>,
+    mut checksum: ResMut<LockstepChecksum>,
+    lockstep: Res<Lockstep<Action>>,
+    salt: Res<Salt>,
+) {
+    for player in 0..lockstep.players() {
+        if lockstep
+            .player(player)
+            .is_some_and(|input| input.just_pressed(Action::Jump))
+        {
+            jumps.0.push((lockstep.tick() + 1, player));
+        }
+    }
+    checksum.add(jumps.0.len());
+    checksum.add(salt.0);
+}
+
+fn collect_desyncs(mut desyncs: ResMut<Desyncs>, mut desync_events: EventReader<LockstepDesync>) {
+    desyncs.0.extend(desync_events.iter().copied());
+}
+
+fn peer(
+    players: usize,
+    local_player: usize,
+    transport: LoopbackTransport,
+    salt: u64,
+) -> HaliaTestApp {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.add_lockstep::<Action>()
+        .init_resource::<Jumps>()
+        .init_resource::<Desyncs>()
+        .insert_resource(Salt(salt))
+        .add_system(jump.in_schedule(CoreSchedule::FixedUpdate))
+        .add_system(collect_desyncs);
+    app.world
+        .resource_mut::<Lockstep<Action>>()
+        .start(players, local_player, transport);
+    app
+}
+
+fn peers(salts: [u64; 2]) -> [HaliaTestApp; 2] {
+    let [a, b] = <[LoopbackTransport; 2]>::try_from(LoopbackTransport::group(2)).unwrap();
+    [peer(2, 0, a, salts[0]), peer(2, 1, b, salts[1])]
+}
+
+fn run(peers: &mut [HaliaTestApp; 2], frames: usize) {
+    for _ in 0..frames {
+        for peer in peers.iter_mut() {
+            peer.frames(1);
+            peer.world.resource_mut::<Input<Action>>().clear();
+        }
+    }
+}
+
+#[test]
+fn loopback_peers_share_input() {
+    let mut peers = peers([0, 0]);
+    peers[1]
+        .world
+        .resource_mut::<Input<Action>>()
+        .press(Action::Jump);
+    run(&mut peers, 10);
+
+    for peer in &peers {
+        let lockstep = peer.world.resource::<Lockstep<Action>>();
+        assert!(lockstep.tick() >= 8);
+        assert!(!lockstep.is_stalled());
+        let jump_tick = Lockstep::<Action>::DEFAULT_INPUT_DELAY + 1;
+        assert_eq!(peer.world.resource::<Jumps>().0, vec![(jump_tick, 1)]);
+        assert!(peer.world.resource::<Desyncs>().0.is_empty());
+    }
+}
+
+#[test]
+fn loopback_peers_detect_desync() {
+    let mut peers = peers([1, 2]);
+    run(&mut peers, 10);
+
+    for (local_player, peer) in peers.iter().enumerate() {
+        let desyncs = &peer.world.resource::<Desyncs>().0;
+        assert!(!desyncs.is_empty());
+        assert!(desyncs
+            .iter()
+            .all(|desync| desync.player == 1 - local_player));
+    }
+}
+
+#[test]
+fn packets_are_only_accepted_from_their_player() {
+    let [a, _, mut c] = <[LoopbackTransport; 3]>::try_from(LoopbackTransport::group(3)).unwrap();
+    let mut peer = peer(3, 0, a, 0);
+    let input_delay = Lockstep::<Action>::DEFAULT_INPUT_DELAY;
+    let next = u8::try_from(input_delay + 1).unwrap();
+    // a length prefix of u64::MAX, which must not be allocated
+    c.send(&[2, 253, 255, 255, 255, 255, 255, 255, 255, 255]);
+    // player 2's input for the next tick, and a forged copy for player 1
+    c.send(&[2, 0, 1, next, 0, 0]);
+    c.send(&[1, 0, 1, next, 0, 0]);
+    for _ in 0..10 {
+        peer.frames(1);
+    }
+
+    let lockstep = peer.world.resource::<Lockstep<Action>>();
+    assert_eq!(lockstep.tick(), input_delay);
+    assert!(lockstep.is_stalled());
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "halia_test")]
+
+use bevy::{
+    input::gamepad::{GamepadConnection, GamepadConnectionEvent, GamepadInfo},
+    prelude::*,
+};
+use halia::{prelude::*, testing::HaliaTestApp};
+
+#[derive(Default, Resource)]
+struct Log(Vec<(bool, bool, Option<u32>)>);
+
+#[derive(Default, Resource)]
+struct SlotEvents(Vec<PlayerSlotEvent>);
+
+fn log(mut log: ResMut<Log>, player_input: Res<FixedInput<PlayerButton>>) {
+    let left = player_input.player(0);
+    let right = player_input.player(1);
+    log.0.push((
+        left.just_pressed(GamepadButtonType::South),
+        left.pressed(GamepadButtonType::South),
+        right.held_ticks(GamepadButtonType::DPadUp),
+    ));
+}
+
+fn collect_slot_events(
+    mut slot_events: ResMut<SlotEvents>,
+    mut player_slot_events: EventReader<PlayerSlotEvent>,
+) {
+    slot_events.0.extend(player_slot_events.iter().cloned());
+}
+
+fn app() -> HaliaTestApp {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.add_player_slots()
+        .insert_resource(
+            PlayerSlots::new(3)
+                .with_player(PlayerDevice::Keyboard(KeyboardLayout::wasd()))
+                .with_player(PlayerDevice::Keyboard(KeyboardLayout::arrows())),
+        )
+        .init_resource::<Log>()
+        .init_resource::<SlotEvents>()
+        .add_systems((log, collect_slot_events).in_schedule(CoreSchedule::FixedUpdate));
+    app
+}
+
+#[test]
+fn each_player_gets_their_own_buttons() {
+    let mut app = app();
+    // a tap within a single frame is still seen
+    app.press(KeyCode::Space).release(KeyCode::Space);
+    app.press(KeyCode::Up).tick(1);
+    app.tick(1);
+    assert_eq!(
+        app.world.resource::<Log>().0,
+        vec![(true, false, Some(0)), (false, false, Some(1))]
+    );
+}
+
+#[test]
+fn gamepads_join_and_leave_free_slots() {
+    let mut app = app();
+    let gamepad = Gamepad::new(0);
+    app.world.send_event(GamepadConnectionEvent::new(
+        gamepad,
+        GamepadConnection::Connected(GamepadInfo {
+            name: "pad".to_owned(),
+        }),
+    ));
+    app.tick(1);
+    app.world.resource_mut::<PlayerSlots>().leave(0).unwrap();
+    app.world.send_event(GamepadConnectionEvent::new(
+        gamepad,
+        GamepadConnection::Disconnected,
+    ));
+    app.tick(1);
+
+    let wasd = PlayerDevice::Keyboard(KeyboardLayout::wasd());
+    let arrows = PlayerDevice::Keyboard(KeyboardLayout::arrows());
+    let pad = PlayerDevice::Gamepad(gamepad);
+    assert_eq!(
+        app.world.resource::<SlotEvents>().0,
+        vec![
+            PlayerSlotEvent::Joined {
+                player: 0,
+                device: wasd.clone(),
+            },
+            PlayerSlotEvent::Joined {
+                player: 1,
+                device: arrows,
+            },
+            PlayerSlotEvent::Joined {
+                player: 2,
+                device: pad.clone(),
+            },
+            PlayerSlotEvent::Left {
+                player: 0,
+                device: wasd,
+            },
+            PlayerSlotEvent::Left {
+                player: 2,
+                device: pad,
+            },
+        ]
+    );
+    assert_eq!(app.world.resource::<PlayerSlots>().player_count(), 1);
+}
@@ -0,0 +1,72 @@
+#![cfg(all(feature = "halia_test", feature = "halia_replay"))]
+
+use bevy::{input::mouse::MouseMotion, prelude::*};
+use halia::{prelude::*, testing::HaliaTestApp};
+
+const STICK: GamepadAxis = GamepadAxis {
+    gamepad: Gamepad { id: 0 },
+    axis_type: GamepadAxisType::LeftStickX,
+};
+
+#[derive(Debug, Default, PartialEq, Resource)]
+struct Log(Vec<(u64, f32, f32, String)>);
+
+fn log(
+    mut log: ResMut<Log>,
+    fixed_tick: Res<FixedTick>,
+    stick: Res<FixedAxis<GamepadAxis>>,
+    mouse: Res<FixedAxis<MouseAxis>>,
+    text_input: Res<FixedTextInput>,
+) {
+    log.0.push((
+        fixed_tick.0,
+        stick.get(STICK),
+        mouse.get(MouseAxis::MotionX),
+        text_input.text(),
+    ));
+}
+
+fn input(app: &mut HaliaTestApp, stick: f32, mouse: f32, char: char) {
+    app.world
+        .resource_mut::<Axis<GamepadAxis>>()
+        .set(STICK, stick);
+    app.world.send_event(MouseMotion {
+        delta: Vec2::new(mouse, 0.),
+    });
+    app.world.send_event(ReceivedCharacter {
+        window: Entity::PLACEHOLDER,
+        char,
+    });
+}
+
+#[test]
+fn playback_restores_analog_and_text_input() {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.add_fixed_text_input()
+        .init_resource::<Log>()
+        .add_system(log.in_schedule(CoreSchedule::FixedUpdate));
+    app.tick(2);
+    app.world.resource_mut::<Log>().0.clear();
+    app.world.resource_mut::<Replay>().start_recording();
+    for (stick, mouse, char) in [(0.25, 3., 'a'), (-0.5, -1., 'b'), (1., 0.5, 'c')] {
+        input(&mut app, stick, mouse, char);
+        app.tick(1);
+    }
+    app.tick(1);
+    let recording = app.world.resource_mut::<Replay>().take_recording();
+    let recorded = std::mem::take(&mut app.world.resource_mut::<Log>().0);
+    assert_eq!(recorded.len(), 4);
+    assert_eq!(recorded[1], (4, -0.5, -1., "b".to_owned()));
+
+    input(&mut app, -1., 10., 'x');
+    app.world.resource_mut::<Replay>().play(recording);
+    for _ in 0..10 {
+        if !app.world.resource::<Replay>().is_playing() {
+            break;
+        }
+        app.tick(1);
+    }
+
+    assert!(!app.world.resource::<Replay>().is_playing());
+    assert_eq!(app.world.resource::<Log>().0, recorded);
+}
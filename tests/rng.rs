@@ -0,0 +1,50 @@
+#![cfg(feature = "halia_test")]
+
+use bevy::prelude::*;
+use halia::{fixed_timestep::FixedSet, prelude::*, testing::HaliaTestApp};
+
+#[derive(Default, Resource)]
+struct Draws(Vec<(u64, u64, u64)>);
+
+fn draw(mut draws: ResMut<Draws>, mut fixed_rng: ResMut<FixedRng>, fixed_tick: Res<FixedTick>) {
+    let stream = fixed_rng.stream(7).u64();
+    draws.0.push((fixed_tick.0, fixed_rng.u64(), stream));
+}
+
+fn disturb(mut fixed_rng: ResMut<FixedRng>) {
+    fixed_rng.u64();
+    fixed_rng.stream(8).u64();
+}
+
+fn app(seed: u64) -> HaliaTestApp {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.insert_resource(FixedRng::new(seed))
+        .init_resource::<Draws>()
+        .add_system(draw.in_schedule(CoreSchedule::FixedUpdate));
+    app
+}
+
+#[test]
+fn draws_only_depend_on_the_seed_and_tick() {
+    let mut app_a = app(1234);
+    app_a.tick(4);
+
+    let mut app_b = app(1234);
+    app_b.add_system(disturb).add_system(
+        disturb
+            .in_schedule(CoreSchedule::FixedUpdate)
+            .in_base_set(FixedSet::First),
+    );
+    app_b.frame().tick(1).frame().frame();
+    app_b.world.resource_mut::<FixedTimeControl>().step(3);
+    app_b.frame();
+
+    let draws = &app_a.world.resource::<Draws>().0;
+    assert_eq!(draws, &app_b.world.resource::<Draws>().0);
+    assert_eq!(draws.len(), 4);
+    assert_ne!(draws[0].1, draws[1].1);
+
+    let mut app_c = app(4321);
+    app_c.tick(4);
+    assert_ne!(draws, &app_c.world.resource::<Draws>().0);
+}
@@ -0,0 +1,62 @@
+#![cfg(all(feature = "halia_test", feature = "halia_rollback"))]
+
+use bevy::prelude::*;
+use halia::{fixed_timestep::InputLatch, prelude::*, testing::HaliaTestApp};
+
+#[derive(Clone, Component, Copy)]
+struct Player;
+
+#[derive(Clone, Component, Copy)]
+struct Dust;
+
+fn dash(
+    mut commands: Commands,
+    mut player_query: Query<&mut Transform2, With<Player>>,
+    keys: Res<FixedInput<KeyCode>>,
+) {
+    if keys.just_pressed(KeyCode::Right) {
+        player_query.single_mut().translation.x += 1.;
+        commands.spawn((Dust, Rollback));
+    }
+}
+
+fn app() -> HaliaTestApp {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.add_rollback_component::<Dust>()
+        .add_system(dash.in_schedule(CoreSchedule::FixedUpdate));
+    app.world.spawn((Player, Rollback, Transform2::IDENTITY));
+    app
+}
+
+fn state(app: &mut HaliaTestApp) -> (u64, f32, usize) {
+    let x = app
+        .world
+        .query_filtered::<&Transform2, With<Player>>()
+        .single(&app.world)
+        .translation
+        .x;
+    let dust = app.world.query::<&Dust>().iter(&app.world).count();
+    (app.world.resource::<FixedTick>().0, x, dust)
+}
+
+#[test]
+fn rolling_back_resimulates_with_corrected_input() {
+    let mut expected = app();
+    expected.tick(2);
+    expected.press(KeyCode::Right).tick(1);
+    expected.release(KeyCode::Right).tick(4);
+
+    let mut app = app();
+    app.tick(6);
+    assert_eq!(state(&mut app), (6, 0., 0));
+    let mut snapshots = app.world.resource_mut::<Snapshots>();
+    snapshots
+        .input_mut::<FixedInput<KeyCode>>(3)
+        .unwrap()
+        .latch(InputLatch::Press(KeyCode::Right));
+    assert!(snapshots.rollback_to(3));
+    app.tick(1);
+
+    assert_eq!(state(&mut app), (7, 1., 1));
+    assert_eq!(state(&mut app), state(&mut expected));
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "halia_test")]
+
+use std::time::Duration;
+
+use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
+use halia::{prelude::*, testing::HaliaTestApp};
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, ScheduleLabel)]
+struct SlowUpdate;
+
+#[derive(Default, Resource)]
+struct Log(Vec<(&'static str, u64, bool)>);
+
+fn log<const SLOW: bool>(
+    mut log: ResMut<Log>,
+    keys: Res<FixedInput<KeyCode>>,
+    fixed_tick: Res<FixedTick>,
+) {
+    let schedule = if SLOW { "slow" } else { "fixed" };
+    log.0
+        .push((schedule, fixed_tick.0, keys.just_pressed(KeyCode::Space)));
+}
+
+#[test]
+fn each_fixed_schedule_has_its_own_ticks_and_input() {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.insert_resource(FixedTime::new(Duration::from_millis(100)))
+        .add_fixed_schedule(SlowUpdate, Duration::from_millis(300))
+        .init_resource::<Log>()
+        .add_system(log::<false>.in_schedule(CoreSchedule::FixedUpdate))
+        .add_system(log::<true>.in_schedule(SlowUpdate));
+    app.press(KeyCode::Space);
+    app.tick(3);
+    app.release(KeyCode::Space).tick(3);
+
+    assert_eq!(
+        app.world.resource::<Log>().0,
+        vec![
+            ("fixed", 1, true),
+            ("fixed", 2, false),
+            ("fixed", 3, false),
+            ("slow", 1, true),
+            ("fixed", 4, false),
+            ("fixed", 5, false),
+            ("fixed", 6, false),
+            ("slow", 2, false),
+        ]
+    );
+    assert_eq!(app.world.resource::<FixedTick>().0, 6);
+}
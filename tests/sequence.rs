@@ -0,0 +1,73 @@
+#![cfg(feature = "halia_test")]
+
+use bevy::prelude::*;
+use halia::{prelude::*, testing::HaliaTestApp};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Move {
+    Fireball,
+    Punch,
+}
+
+#[derive(Default, Resource)]
+struct Moves(Vec<(u64, Move)>);
+
+fn collect_moves(
+    mut moves: ResMut<Moves>,
+    mut input_sequence_events: EventReader<InputSequenceEvent<Move>>,
+    fixed_tick: Res<FixedTick>,
+) {
+    for input_sequence_event in input_sequence_events.iter() {
+        moves.0.push((fixed_tick.0, input_sequence_event.sequence));
+    }
+}
+
+fn app() -> HaliaTestApp {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.add_input_sequences::<KeyCode, Move>()
+        .insert_resource(
+            InputSequences::new()
+                .with_sequence(
+                    Move::Fireball,
+                    InputSequence::new()
+                        .then(SequenceStep::new([KeyCode::S]).without([KeyCode::D]))
+                        .then([KeyCode::S, KeyCode::D])
+                        .then(
+                            SequenceStep::new([KeyCode::D])
+                                .without([KeyCode::S])
+                                .within(2),
+                        )
+                        .then(KeyCode::J)
+                        .with_priority(1),
+                )
+                .with_sequence(Move::Punch, InputSequence::chord([KeyCode::J])),
+        )
+        .init_resource::<Moves>()
+        .add_system(collect_moves.in_schedule(CoreSchedule::FixedUpdate));
+    app
+}
+
+#[test]
+fn higher_priority_sequences_win() {
+    let mut app = app();
+    app.press(KeyCode::S).tick(1);
+    app.press(KeyCode::D).tick(1);
+    app.release(KeyCode::S).tick(1);
+    app.press(KeyCode::J).tick(1);
+    app.release(KeyCode::D).release(KeyCode::J).tick(1);
+    app.press(KeyCode::J).tick(1);
+    assert_eq!(
+        app.world.resource::<Moves>().0,
+        vec![(4, Move::Fireball), (6, Move::Punch)]
+    );
+}
+
+#[test]
+fn steps_must_match_within_their_tolerance() {
+    let mut app = app();
+    app.press(KeyCode::S).tick(1);
+    app.press(KeyCode::D).tick(3);
+    app.release(KeyCode::S).tick(1);
+    app.press(KeyCode::J).tick(1);
+    assert_eq!(app.world.resource::<Moves>().0, vec![(6, Move::Punch)]);
+}
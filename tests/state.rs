@@ -0,0 +1,72 @@
+#![cfg(all(feature = "halia_test", feature = "halia_cleanup"))]
+
+use bevy::prelude::*;
+use halia::{fixed_timestep::FixedSet, prelude::*, testing::HaliaTestApp, Persistent};
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, States)]
+enum GameState {
+    #[default]
+    Playing,
+    GameOver,
+}
+
+#[derive(Default, Resource)]
+struct Log(Vec<(u64, &'static str, GameState)>);
+
+fn log<const LATE: bool>(
+    mut log: ResMut<Log>,
+    state: Res<State<GameState>>,
+    fixed_tick: Res<FixedTick>,
+) {
+    let set = if LATE { "update" } else { "first" };
+    log.0.push((fixed_tick.0, set, state.0));
+}
+
+fn enter_game_over(mut log: ResMut<Log>, fixed_tick: Res<FixedTick>) {
+    log.0.push((fixed_tick.0, "enter", GameState::GameOver));
+}
+
+#[test]
+fn transitions_wait_for_the_next_tick() {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.add_fixed_state::<GameState>()
+        .add_state_cleanup::<GameState>()
+        .init_resource::<Log>()
+        .add_systems(
+            (
+                log::<false>.in_base_set(FixedSet::First),
+                log::<true>.in_base_set(FixedSet::Update),
+            )
+                .in_schedule(CoreSchedule::FixedUpdate),
+        )
+        .add_system(enter_game_over.in_schedule(OnEnter(GameState::GameOver)));
+    let enemy = app.world.spawn_empty().id();
+    let ui = app.world.spawn(Persistent).id();
+    app.tick(1);
+
+    app.world.resource_mut::<FixedTimeControl>().pause();
+    app.world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::GameOver);
+    app.frames(3);
+    assert_eq!(
+        app.world.resource::<State<GameState>>().0,
+        GameState::Playing
+    );
+    assert!(app.world.get_entity(enemy).is_some());
+
+    app.world.resource_mut::<FixedTimeControl>().resume();
+    app.tick(1);
+    assert_eq!(
+        app.world.resource::<Log>().0,
+        vec![
+            (0, "first", GameState::Playing),
+            (1, "update", GameState::Playing),
+            (1, "first", GameState::Playing),
+            (2, "enter", GameState::GameOver),
+            (2, "update", GameState::GameOver),
+        ]
+    );
+    assert!(app.world.get_entity(enemy).is_none());
+    assert!(app.world.get_entity(ui).is_some());
+}
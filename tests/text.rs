@@ -0,0 +1,52 @@
+#![cfg(feature = "halia_test")]
+
+use bevy::{prelude::*, window::Ime};
+use halia::{prelude::*, testing::HaliaTestApp};
+
+#[derive(Default, Resource)]
+struct Name(String);
+
+#[derive(Default, Resource)]
+struct Edits(Vec<usize>);
+
+fn edit_name(text_input: Res<FixedTextInput>, mut name: ResMut<Name>, mut edits: ResMut<Edits>) {
+    text_input.apply(&mut name.0);
+    edits.0.push(text_input.edits().len());
+}
+
+fn type_char(app: &mut HaliaTestApp, char: char) {
+    app.world.send_event(ReceivedCharacter {
+        window: Entity::PLACEHOLDER,
+        char,
+    });
+}
+
+#[test]
+fn text_is_seen_by_exactly_one_tick() {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.add_fixed_text_input()
+        .init_resource::<Name>()
+        .init_resource::<Edits>()
+        .add_system(edit_name.in_schedule(CoreSchedule::FixedUpdate));
+    type_char(&mut app, 'a');
+    app.frame();
+    type_char(&mut app, 'x');
+    type_char(&mut app, '\u{8}');
+    app.world.send_event(Ime::Preedit {
+        window: Entity::PLACEHOLDER,
+        value: "ね".to_owned(),
+        cursor: None,
+    });
+    app.frame();
+    assert_eq!(app.world.resource::<FixedTextInput>().preedit(), "ね");
+    app.world.send_event(Ime::Commit {
+        window: Entity::PLACEHOLDER,
+        value: "猫".to_owned(),
+    });
+    app.world.resource_mut::<FixedTimeControl>().step(2);
+    app.frame();
+
+    assert_eq!(app.world.resource::<Name>().0, "a猫");
+    assert_eq!(app.world.resource::<Edits>().0, vec![4, 0]);
+    assert_eq!(app.world.resource::<FixedTextInput>().preedit(), "");
+}
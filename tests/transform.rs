@@ -0,0 +1,56 @@
+#![cfg(feature = "halia_test")]
+
+use bevy::prelude::*;
+use halia::{fixed_timestep::FixedSet, prelude::*, testing::HaliaTestApp};
+
+#[derive(Component)]
+struct Child;
+
+#[derive(Default, Resource)]
+struct Translations(Vec<Vec3>);
+
+fn record(
+    mut translations: ResMut<Translations>,
+    child_query: Query<&GlobalTransform, With<Child>>,
+) {
+    translations.0.push(child_query.single().translation());
+}
+
+#[test]
+fn entities_unparented_between_ticks_are_propagated() {
+    let mut app = HaliaTestApp::new(HaliaPlugins);
+    app.init_resource::<Translations>().add_system(
+        record
+            .in_schedule(CoreSchedule::FixedUpdate)
+            .in_base_set(FixedSet::Last),
+    );
+    let parent = app
+        .world
+        .spawn(TransformBundle::from_transform(Transform::from_xyz(
+            10., 0., 0.,
+        )))
+        .id();
+    let child = app
+        .world
+        .spawn((
+            TransformBundle::from_transform(Transform::from_xyz(1., 0., 0.)),
+            Child,
+        ))
+        .set_parent(parent)
+        .id();
+    app.tick(1);
+
+    // unparent while paused, for long enough that the removal is no longer reported
+    app.world.resource_mut::<FixedTimeControl>().pause();
+    app.world.entity_mut(child).remove_parent();
+    app.frames(3);
+    // stale, without marking the child's transform as changed
+    *app.world.get_mut::<GlobalTransform>(child).unwrap() = GlobalTransform::from_xyz(11., 0., 0.);
+    app.world.resource_mut::<FixedTimeControl>().resume();
+    app.tick(1);
+
+    assert_eq!(
+        app.world.resource::<Translations>().0,
+        vec![Vec3::new(11., 0., 0.), Vec3::new(1., 0., 0.)]
+    );
+}